@@ -0,0 +1,95 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use elrond_wasm::elrond_codec::*;
+
+use super::{LegacyUnlockMilestone, UnlockMilestone};
+
+pub const COMMUNITY_DISTRIBUTION_VERSION: u8 = 1;
+
+/// Encoded starting with a version byte. Distributions stored before versioning start
+/// with the (big endian) length of `total_amount`, so their first byte is always 0.
+/// Those decode as sealed, with a claim window closing at `spread_epoch`,
+/// until the owner migrates them.
+#[derive(PartialEq, TypeAbi)]
+pub struct CommunityDistribution<BigUint: BigUintApi> {
+    pub total_amount: BigUint,
+    pub spread_epoch: u64,
+    pub claim_start_epoch: u64,
+    pub claim_end_epoch: u64,
+    pub after_planning_amount: BigUint,
+    pub unlock_milestones: Vec<UnlockMilestone>,
+    pub sealed: bool,
+}
+
+impl<BigUint: BigUintApi> TopEncode for CommunityDistribution<BigUint> {
+    fn top_encode<O: TopEncodeOutput>(&self, output: O) -> Result<(), EncodeError> {
+        let mut bytes = Vec::<u8>::new();
+        COMMUNITY_DISTRIBUTION_VERSION.dep_encode(&mut bytes)?;
+        self.total_amount.dep_encode(&mut bytes)?;
+        self.spread_epoch.dep_encode(&mut bytes)?;
+        self.claim_start_epoch.dep_encode(&mut bytes)?;
+        self.claim_end_epoch.dep_encode(&mut bytes)?;
+        self.after_planning_amount.dep_encode(&mut bytes)?;
+        self.unlock_milestones.dep_encode(&mut bytes)?;
+        self.sealed.dep_encode(&mut bytes)?;
+        output.set_slice_u8(bytes.as_slice());
+        Ok(())
+    }
+}
+
+impl<BigUint: BigUintApi> TopDecode for CommunityDistribution<BigUint> {
+    fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
+        let bytes = input.into_boxed_slice_u8();
+        let mut remaining = &*bytes;
+
+        let version = match remaining.first() {
+            Some(version) => *version,
+            None => return Err(DecodeError::INPUT_TOO_SHORT),
+        };
+        let community_distrib = match version {
+            0 => Self::dep_decode_legacy(&mut remaining)?,
+            COMMUNITY_DISTRIBUTION_VERSION => {
+                remaining = &remaining[1..];
+                CommunityDistribution {
+                    total_amount: BigUint::dep_decode(&mut remaining)?,
+                    spread_epoch: u64::dep_decode(&mut remaining)?,
+                    claim_start_epoch: u64::dep_decode(&mut remaining)?,
+                    claim_end_epoch: u64::dep_decode(&mut remaining)?,
+                    after_planning_amount: BigUint::dep_decode(&mut remaining)?,
+                    unlock_milestones: Vec::<UnlockMilestone>::dep_decode(&mut remaining)?,
+                    sealed: bool::dep_decode(&mut remaining)?,
+                }
+            }
+            _ => return Err(DecodeError::INVALID_VALUE),
+        };
+
+        if !remaining.is_empty() {
+            return Err(DecodeError::INPUT_TOO_LONG);
+        }
+        Ok(community_distrib)
+    }
+}
+
+impl<BigUint: BigUintApi> CommunityDistribution<BigUint> {
+    /// Legacy layout: no claim window, no sealed flag and whole percent milestones.
+    fn dep_decode_legacy(remaining: &mut &[u8]) -> Result<Self, DecodeError> {
+        let total_amount = BigUint::dep_decode(remaining)?;
+        let spread_epoch = u64::dep_decode(remaining)?;
+        let after_planning_amount = BigUint::dep_decode(remaining)?;
+        let unlock_milestones = Vec::<LegacyUnlockMilestone>::dep_decode(remaining)?
+            .into_iter()
+            .map(LegacyUnlockMilestone::into_unlock_milestone)
+            .collect();
+
+        Ok(CommunityDistribution {
+            total_amount,
+            spread_epoch,
+            claim_start_epoch: spread_epoch,
+            claim_end_epoch: spread_epoch,
+            after_planning_amount,
+            unlock_milestones,
+            sealed: true,
+        })
+    }
+}
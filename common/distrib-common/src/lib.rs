@@ -3,8 +3,19 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
+mod community_distribution;
+mod locked_token_attributes;
+mod token_metadata;
+
+pub use community_distribution::*;
+pub use locked_token_attributes::*;
+pub use token_metadata::*;
+
 type Nonce = u64;
 
+/// Unlock percents are expressed in thousandths of a percent.
+pub const PERCENTAGE_TOTAL: u64 = 100_000;
+
 #[derive(TopEncode, TopDecode, PartialEq, TypeAbi)]
 pub struct UserAssetKey {
     pub user_address: Address,
@@ -15,20 +26,7 @@ pub struct UserAssetKey {
 #[derive(TopEncode, TopDecode, PartialEq, TypeAbi, NestedEncode, NestedDecode, Clone, Copy)]
 pub struct UnlockMilestone {
     pub unlock_epoch: u64,
-    pub unlock_percent: u8,
-}
-
-#[derive(TopEncode, TopDecode, PartialEq, TypeAbi)]
-pub struct CommunityDistribution<BigUint: BigUintApi> {
-    pub total_amount: BigUint,
-    pub spread_epoch: u64,
-    pub after_planning_amount: BigUint,
-    pub unlock_milestones: Vec<UnlockMilestone>,
-}
-
-#[derive(TopEncode, TopDecode, TypeAbi)]
-pub struct LockedTokenAttributes {
-    pub unlock_milestones: Vec<UnlockMilestone>,
+    pub unlock_percent: u64,
 }
 
 #[derive(TopEncode, TopDecode, TypeAbi)]
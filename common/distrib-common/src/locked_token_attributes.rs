@@ -0,0 +1,125 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use elrond_wasm::elrond_codec::*;
+
+use super::{UnlockMilestone, PERCENTAGE_TOTAL};
+
+pub const LOCKED_TOKEN_ATTRIBUTES_VERSION: u8 = 1;
+const LEGACY_PERCENTAGE_TOTAL: u64 = 100;
+
+/// Milestone format of the data stored before unlock percents were expressed
+/// in thousandths, with the unlock percent expressed in whole percents.
+#[derive(NestedDecode)]
+pub(crate) struct LegacyUnlockMilestone {
+    unlock_epoch: u64,
+    unlock_percent: u8,
+}
+
+impl LegacyUnlockMilestone {
+    pub(crate) fn into_unlock_milestone(self) -> UnlockMilestone {
+        UnlockMilestone {
+            unlock_epoch: self.unlock_epoch,
+            unlock_percent: (self.unlock_percent as u64) * PERCENTAGE_TOTAL
+                / LEGACY_PERCENTAGE_TOTAL,
+        }
+    }
+}
+
+/// Unlocks pro-rata between `start_epoch` and `end_epoch`.
+/// Nothing unlocks before `cliff_epoch`. A `cliff_epoch` equal to `start_epoch` means no cliff.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, TypeAbi, Clone, Copy)]
+pub struct LinearUnlockSchedule {
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+    pub cliff_epoch: u64,
+}
+
+/// Lets `admin` revoke grant `grant_id`, which stops vesting for every nonce carrying it.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, TypeAbi, Clone)]
+pub struct RevocableGrant {
+    pub grant_id: u64,
+    pub beneficiary: Address,
+    pub admin: Address,
+}
+
+/// Exactly one of the schedules is used: `linear_schedule` if set, `unlock_milestones` otherwise.
+/// The schedule is always the original one. `unlocked_percent` is the part of it
+/// already released, so the remaining amount unlocks along the rest of the curve.
+///
+/// `asset_token_id` is the token the assets unlock into. When empty,
+/// it is the default asset token of the factory that created them.
+///
+/// Attributes are encoded starting with a version byte. Nonces minted before versioning
+/// start with the (big endian) length of the milestones list, so their first byte is always 0.
+#[derive(PartialEq, TypeAbi, Clone)]
+pub struct LockedTokenAttributes {
+    pub unlock_milestones: Vec<UnlockMilestone>,
+    pub linear_schedule: Option<LinearUnlockSchedule>,
+    pub unlocked_percent: u64,
+    pub revocable_grant: Option<RevocableGrant>,
+    pub asset_token_id: Option<TokenIdentifier>,
+}
+
+impl TopEncode for LockedTokenAttributes {
+    fn top_encode<O: TopEncodeOutput>(&self, output: O) -> Result<(), EncodeError> {
+        let mut bytes = Vec::<u8>::new();
+        LOCKED_TOKEN_ATTRIBUTES_VERSION.dep_encode(&mut bytes)?;
+        self.unlock_milestones.dep_encode(&mut bytes)?;
+        self.linear_schedule.dep_encode(&mut bytes)?;
+        self.unlocked_percent.dep_encode(&mut bytes)?;
+        self.revocable_grant.dep_encode(&mut bytes)?;
+        self.asset_token_id.dep_encode(&mut bytes)?;
+        output.set_slice_u8(bytes.as_slice());
+        Ok(())
+    }
+}
+
+impl TopDecode for LockedTokenAttributes {
+    fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
+        let bytes = input.into_boxed_slice_u8();
+        let mut remaining = &*bytes;
+
+        let version = match remaining.first() {
+            Some(version) => *version,
+            None => return Err(DecodeError::INPUT_TOO_SHORT),
+        };
+        let attributes = match version {
+            0 => Self::dep_decode_legacy(&mut remaining)?,
+            LOCKED_TOKEN_ATTRIBUTES_VERSION => {
+                remaining = &remaining[1..];
+                LockedTokenAttributes {
+                    unlock_milestones: Vec::<UnlockMilestone>::dep_decode(&mut remaining)?,
+                    linear_schedule: Option::<LinearUnlockSchedule>::dep_decode(&mut remaining)?,
+                    unlocked_percent: u64::dep_decode(&mut remaining)?,
+                    revocable_grant: Option::<RevocableGrant>::dep_decode(&mut remaining)?,
+                    asset_token_id: Option::<TokenIdentifier>::dep_decode(&mut remaining)?,
+                }
+            }
+            _ => return Err(DecodeError::INVALID_VALUE),
+        };
+
+        if !remaining.is_empty() {
+            return Err(DecodeError::INPUT_TOO_LONG);
+        }
+        Ok(attributes)
+    }
+}
+
+impl LockedTokenAttributes {
+    /// Legacy layout: only whole percent milestones.
+    fn dep_decode_legacy(remaining: &mut &[u8]) -> Result<Self, DecodeError> {
+        let unlock_milestones = Vec::<LegacyUnlockMilestone>::dep_decode(remaining)?
+            .into_iter()
+            .map(LegacyUnlockMilestone::into_unlock_milestone)
+            .collect();
+
+        Ok(LockedTokenAttributes {
+            unlock_milestones,
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: None,
+        })
+    }
+}
@@ -0,0 +1,96 @@
+elrond_wasm::imports!();
+
+use super::{LockedTokenAttributes, WrappedFarmTokenAttributes, WrappedLpTokenAttributes};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+pub fn push_decimal(bytes: &mut Vec<u8>, value: u64) {
+    let mut digits = Vec::<u8>::new();
+    let mut remaining = value;
+    loop {
+        digits.push(b'0' + (remaining % 10) as u8);
+        remaining /= 10;
+        if remaining == 0 {
+            break;
+        }
+    }
+    bytes.extend(digits.iter().rev());
+}
+
+pub fn push_hex(bytes: &mut Vec<u8>, data: &[u8]) {
+    for byte in data.iter() {
+        bytes.push(HEX_DIGITS[(byte >> 4) as usize]);
+        bytes.push(HEX_DIGITS[(byte & 0x0f) as usize]);
+    }
+}
+
+impl LockedTokenAttributes {
+    /// First epoch in which the whole schedule is unlocked.
+    pub fn fully_unlocked_epoch(&self) -> u64 {
+        match &self.linear_schedule {
+            Some(linear_schedule) => linear_schedule.end_epoch,
+            None => self
+                .unlock_milestones
+                .iter()
+                .map(|milestone| milestone.unlock_epoch + 1)
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// For example `Locked MEX-abcdef until epoch 420`
+    /// or `Locked MEX-abcdef linear epochs 300-420 revocable`.
+    pub fn token_name(&self, asset_token_id: &TokenIdentifier) -> BoxedBytes {
+        let mut name = Vec::<u8>::new();
+        name.extend_from_slice(b"Locked ");
+        name.extend_from_slice(asset_token_id.as_esdt_identifier());
+        match &self.linear_schedule {
+            Some(linear_schedule) => {
+                name.extend_from_slice(b" linear epochs ");
+                push_decimal(&mut name, linear_schedule.start_epoch);
+                name.push(b'-');
+                push_decimal(&mut name, linear_schedule.end_epoch);
+            }
+            None => {
+                name.extend_from_slice(b" until epoch ");
+                push_decimal(&mut name, self.fully_unlocked_epoch());
+            }
+        }
+        if self.revocable_grant.is_some() {
+            name.extend_from_slice(b" revocable");
+        }
+        BoxedBytes::from(name.as_slice())
+    }
+}
+
+impl<BigUint: BigUintApi> WrappedLpTokenAttributes<BigUint> {
+    /// For example `Wrapped MEXEGLD-abcdef with LKMEX-abcdef nonce 12`.
+    pub fn token_name(&self) -> BoxedBytes {
+        let mut name = Vec::<u8>::new();
+        name.extend_from_slice(b"Wrapped ");
+        name.extend_from_slice(self.lp_token_id.as_esdt_identifier());
+        name.extend_from_slice(b" with ");
+        name.extend_from_slice(self.locked_assets_token_id.as_esdt_identifier());
+        name.extend_from_slice(b" nonce ");
+        push_decimal(&mut name, self.locked_assets_nonce);
+        BoxedBytes::from(name.as_slice())
+    }
+}
+
+impl WrappedFarmTokenAttributes {
+    /// For example `Wrapped FARM-abcdef nonce 3 farming WLPTOK-abcdef nonce 12`.
+    pub fn token_name(&self) -> BoxedBytes {
+        let mut name = Vec::<u8>::new();
+        name.extend_from_slice(b"Wrapped ");
+        name.extend_from_slice(self.farm_token_id.as_esdt_identifier());
+        name.extend_from_slice(b" nonce ");
+        push_decimal(&mut name, self.farm_token_nonce);
+        name.extend_from_slice(b" farming ");
+        name.extend_from_slice(self.farmed_token_id.as_esdt_identifier());
+        if self.farmed_token_nonce != 0 {
+            name.extend_from_slice(b" nonce ");
+            push_decimal(&mut name, self.farmed_token_nonce);
+        }
+        BoxedBytes::from(name.as_slice())
+    }
+}
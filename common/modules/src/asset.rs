@@ -2,18 +2,38 @@ elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
 const MINT_TOKENS_GAS_LIMIT: u64 = 5000000;
+const BURN_TOKENS_GAS_LIMIT: u64 = 5000000;
 
 #[elrond_wasm_derive::module]
 pub trait AssetModule {
     fn mint_and_send_assets(&self, address: &Address, amount: &Self::BigUint) {
+        self.mint_and_send_asset_tokens(&self.asset_token_id().get(), address, amount);
+    }
+
+    fn mint_and_send_asset_tokens(
+        &self,
+        token_id: &TokenIdentifier,
+        address: &Address,
+        amount: &Self::BigUint,
+    ) {
         if amount > &0 {
-            let token_id = self.asset_token_id().get();
             self.send().esdt_local_mint(
                 MINT_TOKENS_GAS_LIMIT,
                 &token_id.as_esdt_identifier(),
                 amount,
             );
-            self.send().transfer_tokens(&token_id, 0, amount, address);
+            self.send().transfer_tokens(token_id, 0, amount, address);
+        }
+    }
+
+    fn burn_assets(&self, amount: &Self::BigUint) {
+        self.burn_asset_tokens(&self.asset_token_id().get(), amount);
+    }
+
+    fn burn_asset_tokens(&self, token_id: &TokenIdentifier, amount: &Self::BigUint) {
+        if amount > &0 {
+            self.send()
+                .burn_tokens(token_id, 0, amount, BURN_TOKENS_GAS_LIMIT);
         }
     }
 
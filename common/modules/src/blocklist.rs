@@ -0,0 +1,38 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use elrond_wasm::{require, sc_error};
+
+#[elrond_wasm_derive::module]
+pub trait BlocklistModule {
+    #[endpoint(addToBlocklist)]
+    fn add_to_blocklist(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.blocklist().insert(address);
+        Ok(())
+    }
+
+    #[endpoint(removeFromBlocklist)]
+    fn remove_from_blocklist(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(
+            self.blocklist().contains(&address),
+            "Address not blocklisted"
+        );
+        self.blocklist().remove(&address);
+        Ok(())
+    }
+
+    fn is_blocklisted(&self, address: &Address) -> bool {
+        self.blocklist().contains(address)
+    }
+
+    fn require_not_blocklisted(&self, address: &Address) -> SCResult<()> {
+        require!(!self.is_blocklisted(address), "Address is blocklisted");
+        Ok(())
+    }
+
+    #[view(getBlocklist)]
+    #[storage_mapper("blocklist")]
+    fn blocklist(&self) -> SetMapper<Self::Storage, Address>;
+}
@@ -1,7 +1,11 @@
 #![no_std]
 
 pub mod asset;
+pub mod blocklist;
 pub mod global_op;
+pub mod token_metadata;
 
 pub use asset::*;
+pub use blocklist::*;
 pub use global_op::*;
+pub use token_metadata::*;
@@ -0,0 +1,47 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use distrib_common::*;
+use elrond_wasm::{require, sc_error};
+
+/// Created tokens get the URI template followed by the hex encoded hash of their attributes,
+/// which is also set as their hash. Without a template, they get no URI.
+#[elrond_wasm_derive::module]
+pub trait TokenMetadataModule {
+    #[endpoint(setUriTemplate)]
+    fn set_uri_template(&self, uri_template: BoxedBytes) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(!uri_template.is_empty(), "Empty URI template");
+        self.uri_template().set(&uri_template);
+        Ok(())
+    }
+
+    #[endpoint(removeUriTemplate)]
+    fn remove_uri_template(&self) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.uri_template().clear();
+        Ok(())
+    }
+
+    fn hash_token_attributes(&self, encoded_attributes: &[u8]) -> H256 {
+        self.crypto().keccak256(encoded_attributes)
+    }
+
+    fn get_token_uris(&self, attributes_hash: &H256) -> Vec<BoxedBytes> {
+        let mut uris = Vec::new();
+        if self.uri_template().is_empty() {
+            uris.push(BoxedBytes::empty());
+            return uris;
+        }
+
+        let mut uri = Vec::<u8>::new();
+        uri.extend_from_slice(self.uri_template().get().as_slice());
+        push_hex(&mut uri, attributes_hash.as_bytes());
+        uris.push(BoxedBytes::from(uri.as_slice()));
+        uris
+    }
+
+    #[view(getUriTemplate)]
+    #[storage_mapper("uri_template")]
+    fn uri_template(&self) -> SingleValueMapper<Self::Storage, BoxedBytes>;
+}
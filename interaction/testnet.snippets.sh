@@ -26,7 +26,7 @@ deployLockedAssetContract() {
           --gas-limit=1499999999 \
           --proxy=${PROXY} --chain=${CHAIN_ID} \
           --bytecode="../sc-locked-asset-factory/output/sc-locked-asset-factory.wasm" \
-          --arguments $token_identifier 0x000000000000017C64 \
+          --arguments $token_identifier 0x000000000000017C00000000000186A0 \
           --outfile="deploy-locked-asset-internal.interaction.json" --send || return
     
     ADDRESS=$(erdpy data parse --file="deploy-locked-asset-internal.interaction.json" --expression="data['emitted_tx']['address']")
@@ -46,7 +46,7 @@ upgradeLockedAssetContract() {
         --gas-limit=1499999999 \
         --proxy=${PROXY} --chain=${CHAIN_ID} \
         --bytecode="../sc-locked-asset-factory/output/sc-locked-asset-factory.wasm" \
-        --arguments $token_identifier 0x000000000000017C64 \
+        --arguments $token_identifier 0x000000000000017C00000000000186A0 \
         --outfile="upgrade-locked-asset-internal.interaction.json" --send || return
 
     echo ""
@@ -0,0 +1,81 @@
+elrond_wasm::imports!();
+
+const ADDRESS_LENGTH: usize = 32;
+const VARINT_DATA_BITS: u32 = 7;
+const VARINT_MAX_SHIFT: u32 = 63;
+const VARINT_MAX_LAST_BYTE: u8 = 1;
+const VARINT_CONTINUATION_BIT: u8 = 0x80;
+const HEADER_NEW_ADDRESS: u64 = 0x01;
+const HEADER_REGISTER_ADDRESS: u64 = 0x02;
+
+pub enum PackedUserAddress {
+    New(Address, bool),
+    RegistryIndex(usize),
+}
+
+/// Reads entries of the form `header varint | [address] | amount varint`.
+/// If the lowest bit of the header is set, a full 32-byte address follows it,
+/// and the second bit tells if it should be added to the address registry.
+/// Otherwise the header shifted right by one is an index in the address registry.
+/// Amounts are unsigned LEB128 varints.
+pub struct PackedUserAssetsReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PackedUserAssetsReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        PackedUserAssetsReader { data, position: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    pub fn next_entry(&mut self) -> Option<(PackedUserAddress, u64)> {
+        let header = self.read_varint()?;
+        let user_address = if header & HEADER_NEW_ADDRESS != 0 {
+            if header > HEADER_NEW_ADDRESS | HEADER_REGISTER_ADDRESS {
+                return None;
+            }
+            let register = header & HEADER_REGISTER_ADDRESS != 0;
+            PackedUserAddress::New(self.read_address()?, register)
+        } else {
+            let index = header >> 1;
+            if index > u32::MAX as u64 {
+                return None;
+            }
+            PackedUserAddress::RegistryIndex(index as usize)
+        };
+        let amount = self.read_varint()?;
+        Some((user_address, amount))
+    }
+
+    fn read_address(&mut self) -> Option<Address> {
+        let end = self.position + ADDRESS_LENGTH;
+        if end > self.data.len() {
+            return None;
+        }
+        let address = Address::from_slice(&self.data[self.position..end]);
+        self.position = end;
+        Some(address)
+    }
+
+    fn read_varint(&mut self) -> Option<u64> {
+        let mut result = 0u64;
+        let mut shift = 0u32;
+        loop {
+            let byte = *self.data.get(self.position)?;
+            self.position += 1;
+            // Only the lowest bit of the tenth byte still fits in a u64.
+            if shift == VARINT_MAX_SHIFT && byte > VARINT_MAX_LAST_BYTE {
+                return None;
+            }
+            result |= ((byte & !VARINT_CONTINUATION_BIT) as u64) << shift;
+            if byte & VARINT_CONTINUATION_BIT == 0 {
+                return Some(result);
+            }
+            shift += VARINT_DATA_BITS;
+        }
+    }
+}
@@ -3,14 +3,19 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
+use compact::*;
 use distrib_common::*;
 use modules::*;
 
+mod compact;
+
 const GAS_CHECK_FREQUENCY: usize = 100;
-const MAX_CLAIMABLE_DISTRIBUTION_ROUNDS: usize = 4;
+const LEGACY_CLAIMABLE_DISTRIBUTION_ROUNDS: usize = 4;
 
 #[elrond_wasm_derive::contract]
-pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModule {
+pub trait EsdtDistribution:
+    asset::AssetModule + global_op::GlobalOperationModule + blocklist::BlocklistModule
+{
     #[proxy]
     fn locked_asset_factory_proxy(
         &self,
@@ -22,6 +27,9 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         self.asset_token_id().set(&asset_token_id);
         self.locked_asset_factory_address()
             .set(&locked_asset_factory_address);
+        if self.community_distribution_list().is_empty() {
+            self.community_distribs_migrated().set(&true);
+        }
     }
 
     #[endpoint(startGlobalOperation)]
@@ -38,19 +46,78 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         Ok(())
     }
 
+    /// Distributions stored before claim windows existed stay closed until this is called.
+    /// As before, only the last four of them can be claimed, now until `claim_end_epoch`.
+    /// The last one is reopened for uploads if it is not claimable yet.
+    #[endpoint(migrateCommunityDistributions)]
+    fn migrate_community_distribs(&self, claim_end_epoch: u64) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.require_global_op_ongoing()?;
+        require!(
+            !self.community_distribs_migrated().get(),
+            "Community distributions already migrated"
+        );
+        let current_epoch = self.blockchain().get_block_epoch();
+        let last_spread_epoch = self
+            .community_distribution_list()
+            .front()
+            .map(|community_distrib| community_distrib.spread_epoch)
+            .unwrap_or_default();
+        require!(
+            claim_end_epoch >= current_epoch && claim_end_epoch >= last_spread_epoch,
+            "Claim end epoch in the past"
+        );
+
+        let mut community_distribs = Vec::new();
+        while let Some(community_distrib) = self.community_distribution_list().pop_front() {
+            community_distribs.push(community_distrib);
+        }
+        for (index, mut community_distrib) in community_distribs.into_iter().enumerate() {
+            if index < LEGACY_CLAIMABLE_DISTRIBUTION_ROUNDS {
+                community_distrib.claim_end_epoch = claim_end_epoch;
+            }
+            if index == 0 && community_distrib.claim_start_epoch > current_epoch {
+                community_distrib.sealed = false;
+            }
+            self.community_distribution_list()
+                .push_back(community_distrib);
+        }
+        self.community_distribs_migrated().set(&true);
+        Ok(())
+    }
+
     #[endpoint(setCommunityDistribution)]
     fn set_community_distrib(
         &self,
         total_amount: Self::BigUint,
         spread_epoch: u64,
+        claim_start_epoch: u64,
+        claim_end_epoch: u64,
         #[var_args] unlock_milestones: VarArgs<UnlockMilestone>,
     ) -> SCResult<()> {
         only_owner!(self, "Permission denied");
         self.require_global_op_ongoing()?;
+        self.require_community_distribs_migrated()?;
         require!(
             spread_epoch >= self.blockchain().get_block_epoch(),
             "Spread epoch in the past"
         );
+        require!(
+            spread_epoch <= claim_start_epoch,
+            "Claim start epoch before spread epoch"
+        );
+        require!(
+            claim_start_epoch <= claim_end_epoch,
+            "Claim end epoch before claim start epoch"
+        );
+        require!(
+            self.community_distribution_list()
+                .front()
+                .map(|community_distrib| community_distrib.claim_end_epoch)
+                .unwrap_or_default()
+                <= claim_end_epoch,
+            "Claim end epoch before previous community distribution's"
+        );
         require!(
             self.community_distribution_list()
                 .front()
@@ -63,8 +130,11 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         let distrib = CommunityDistribution {
             total_amount: total_amount.clone(),
             spread_epoch,
+            claim_start_epoch,
+            claim_end_epoch,
             after_planning_amount: total_amount,
             unlock_milestones: unlock_milestones.into_vec(),
+            sealed: false,
         };
         self.community_distribution_list().push_front(distrib);
         Ok(())
@@ -75,7 +145,7 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         &self,
         unlock_milestones: &VarArgs<UnlockMilestone>,
     ) -> SCResult<()> {
-        let mut percents_sum: u8 = 0;
+        let mut percents_sum: u64 = 0;
         let mut last_milestone_unlock_epoch: u64 = 0;
         for milestone in unlock_milestones.0.clone() {
             require!(
@@ -83,14 +153,17 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
                 "Unlock epochs not in order"
             );
             require!(
-                milestone.unlock_percent <= 100,
-                "Unlock percent more than 100"
+                milestone.unlock_percent <= PERCENTAGE_TOTAL,
+                "Unlock percent more than 100%"
             );
             last_milestone_unlock_epoch = milestone.unlock_epoch;
             percents_sum += milestone.unlock_percent;
         }
         if !unlock_milestones.is_empty() {
-            require!(percents_sum == 100, "Percents do not sum up to 100");
+            require!(
+                percents_sum == PERCENTAGE_TOTAL,
+                "Percents do not sum up to 100%"
+            );
         }
         Ok(())
     }
@@ -117,24 +190,97 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         only_owner!(self, "Permission denied");
         self.require_global_op_ongoing()?;
         self.require_community_distribution_list_not_empty()?;
-        require!(
-            !self
-                .community_distribution_list()
-                .front()
-                .unwrap()
-                .unlock_milestones
-                .is_empty(),
-            "No unlock milestones set"
-        );
         require!(!user_assets.is_empty(), "Empty assets vec");
         self.add_all_user_assets_to_map(spread_epoch, user_assets, true)
     }
 
+    #[endpoint(setPerUserDistributedAssetsCompact)]
+    fn set_per_user_distributed_assets_compact(
+        &self,
+        spread_epoch: u64,
+        locked_assets: bool,
+        amount_unit: Self::BigUint,
+        packed_user_assets: BoxedBytes,
+    ) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.require_global_op_ongoing()?;
+        self.require_community_distribution_list_not_empty()?;
+        require!(amount_unit > 0, "Zero amount unit");
+        require!(!packed_user_assets.is_empty(), "Empty assets vec");
+
+        let mut user_assets = Vec::new();
+        let mut reader = PackedUserAssetsReader::new(packed_user_assets.as_slice());
+        while !reader.is_empty() {
+            let (packed_user_address, packed_amount) = match reader.next_entry() {
+                Some(entry) => entry,
+                None => return sc_error!("Bad packed user assets"),
+            };
+            let user_address = match packed_user_address {
+                PackedUserAddress::New(user_address, register) => {
+                    if register {
+                        self.register_address(&user_address);
+                    }
+                    user_address
+                }
+                PackedUserAddress::RegistryIndex(index) => {
+                    require!(
+                        index >= 1 && index <= self.address_registry().len(),
+                        "Bad address registry index"
+                    );
+                    self.address_registry().get(index)
+                }
+            };
+            let asset_amount = amount_unit.clone() * Self::BigUint::from(packed_amount);
+            user_assets.push((user_address, asset_amount));
+        }
+        self.add_user_assets_to_map(spread_epoch, user_assets, locked_assets)
+    }
+
+    #[endpoint(removePerUserDistributedAssets)]
+    fn remove_per_user_distributed_assets(
+        &self,
+        spread_epoch: u64,
+        locked_asset: bool,
+        #[var_args] user_addresses: VarArgs<Address>,
+    ) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.require_global_op_ongoing()?;
+        self.require_community_distribution_list_not_empty()?;
+        require!(!user_addresses.is_empty(), "Empty addresses vec");
+
+        let mut community_distrib = self.get_open_community_distrib(spread_epoch)?;
+        for user_address in user_addresses.into_vec() {
+            let user_asset_key = UserAssetKey {
+                user_address,
+                spread_epoch,
+                locked_asset,
+            };
+            match self.user_asset_map().remove(&user_asset_key) {
+                Some(asset_amount) => community_distrib.after_planning_amount += asset_amount,
+                None => return sc_error!("User asset entry not found"),
+            }
+        }
+        self.update_community_distrib(community_distrib);
+        Ok(())
+    }
+
+    #[endpoint(sealCommunityDistribution)]
+    fn seal_community_distrib(&self, spread_epoch: u64) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.require_global_op_ongoing()?;
+        self.require_community_distribution_list_not_empty()?;
+        let mut community_distrib = self.get_open_community_distrib(spread_epoch)?;
+        community_distrib.sealed = true;
+        self.update_community_distrib(community_distrib);
+        Ok(())
+    }
+
     #[endpoint(claimAssets)]
     fn claim_assets(&self) -> SCResult<Self::BigUint> {
         self.require_global_op_not_ongoing()?;
         self.require_community_distribution_list_not_empty()?;
         let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         let (assets_amounts, _) = self.calculate_user_assets(&caller, false, true);
         let cummulated_amount = self.sum_of(&assets_amounts);
         self.mint_and_send_assets(&caller, &cummulated_amount);
@@ -146,6 +292,7 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         self.require_global_op_not_ongoing()?;
         self.require_community_distribution_list_not_empty()?;
         let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         let (assets_amounts, unlock_milestones_vec) =
             self.calculate_user_assets(&caller, true, true);
         let to = self.locked_asset_factory_address().get();
@@ -167,9 +314,72 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
     }
 
     #[endpoint(clearUnclaimableAssets)]
-    fn clear_unclaimable_assets(&self) -> SCResult<usize> {
-        let biggest_unclaimable_asset_epoch = self.get_biggest_unclaimable_asset_epoch();
-        self.undo_user_assets_between_epochs(0, biggest_unclaimable_asset_epoch)
+    fn clear_unclaimable_assets(&self) -> SCResult<MultiResult2<usize, Self::BigUint>> {
+        only_owner!(self, "Permission denied");
+        self.require_global_op_ongoing()?;
+        self.require_community_distribution_list_not_empty()?;
+        let expired_distrib_epochs = self.get_expired_distribution_epochs();
+        if expired_distrib_epochs.is_empty() {
+            return Ok((0, Self::BigUint::zero()).into());
+        }
+        let (removed_entries, retained_epochs, all_entries_visited) = self.remove_asset_entries(
+            |spread_epoch| expired_distrib_epochs.contains(&spread_epoch),
+            true,
+        );
+        if all_entries_visited {
+            self.remove_expired_community_distribs(&retained_epochs);
+        }
+
+        let mut cleared_per_epoch = Vec::<(u64, Self::BigUint)>::new();
+        for (user_asset_key, asset_amount) in removed_entries.iter() {
+            match cleared_per_epoch
+                .iter_mut()
+                .find(|(spread_epoch, _)| *spread_epoch == user_asset_key.spread_epoch)
+            {
+                Some((_, cleared_amount)) => *cleared_amount += asset_amount,
+                None => cleared_per_epoch.push((user_asset_key.spread_epoch, asset_amount.clone())),
+            }
+        }
+
+        let mut total_cleared_amount = Self::BigUint::zero();
+        for (spread_epoch, cleared_amount) in cleared_per_epoch.iter() {
+            let new_cleared_amount =
+                self.cleared_amount(*spread_epoch).get() + cleared_amount.clone();
+            self.cleared_amount(*spread_epoch).set(&new_cleared_amount);
+            total_cleared_amount += cleared_amount;
+        }
+        self.redirect_cleared_assets(&total_cleared_amount);
+
+        Ok((removed_entries.len(), total_cleared_amount).into())
+    }
+
+    #[endpoint(setTreasuryAddress)]
+    fn set_treasury_address(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.treasury_address().set(&address);
+        Ok(())
+    }
+
+    #[endpoint(removeTreasuryAddress)]
+    fn remove_treasury_address(&self) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.treasury_address().clear();
+        Ok(())
+    }
+
+    #[endpoint(mintRecoverableSupply)]
+    fn mint_recoverable_supply(&self) -> SCResult<Self::BigUint> {
+        only_owner!(self, "Permission denied");
+        require!(
+            !self.treasury_address().is_empty(),
+            "No treasury address set"
+        );
+        let recoverable_supply = self.recoverable_supply().get();
+        require!(recoverable_supply > 0, "Nothing to recover");
+
+        self.recoverable_supply().clear();
+        self.mint_and_send_assets(&self.treasury_address().get(), &recoverable_supply);
+        Ok(recoverable_supply)
     }
 
     #[endpoint(undoLastCommunityDistribution)]
@@ -222,6 +432,25 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
             .into()
     }
 
+    #[view(getAddressRegistrySize)]
+    fn get_address_registry_size(&self) -> usize {
+        self.address_registry().len()
+    }
+
+    #[view(getLastCommunityDistributionClaimWindow)]
+    fn get_last_community_distrib_claim_window(&self) -> MultiResult2<u64, u64> {
+        self.community_distribution_list()
+            .front()
+            .map(|last_community_distrib| {
+                (
+                    last_community_distrib.claim_start_epoch,
+                    last_community_distrib.claim_end_epoch,
+                )
+            })
+            .unwrap_or((0u64, 0u64))
+            .into()
+    }
+
     #[view(getLastCommunityDistributionUnlockMilestones)]
     fn get_last_community_distrib_unlock_milestones(&self) -> MultiResultVec<UnlockMilestone> {
         self.community_distribution_list()
@@ -237,26 +466,78 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         user_assets: VarArgs<MultiArg2<Address, Self::BigUint>>,
         locked_assets: bool,
     ) -> SCResult<()> {
-        let mut last_community_distrib = self.community_distribution_list().front().unwrap();
-        require!(
-            spread_epoch == last_community_distrib.spread_epoch,
-            "Bad spread epoch"
-        );
-        for user_asset_multiarg in user_assets.into_vec() {
-            let (user_address, asset_amount) = user_asset_multiarg.into_tuple();
+        let user_assets = user_assets
+            .into_vec()
+            .into_iter()
+            .map(|user_asset_multiarg| user_asset_multiarg.into_tuple())
+            .collect();
+        self.add_user_assets_to_map(spread_epoch, user_assets, locked_assets)
+    }
+
+    fn add_user_assets_to_map(
+        &self,
+        spread_epoch: u64,
+        user_assets: Vec<(Address, Self::BigUint)>,
+        locked_assets: bool,
+    ) -> SCResult<()> {
+        let mut community_distrib = self.get_open_community_distrib(spread_epoch)?;
+        if locked_assets {
             require!(
-                last_community_distrib.after_planning_amount >= asset_amount,
+                !community_distrib.unlock_milestones.is_empty(),
+                "No unlock milestones set"
+            );
+        }
+        for (user_address, asset_amount) in user_assets.into_iter() {
+            require!(
+                community_distrib.after_planning_amount >= asset_amount,
                 "User assets sums above community total assets"
             );
-            last_community_distrib.after_planning_amount -= asset_amount.clone();
+            community_distrib.after_planning_amount -= asset_amount.clone();
             self.add_user_asset_entry(user_address, asset_amount, spread_epoch, locked_assets)?;
         }
-        self.community_distribution_list().pop_front();
-        self.community_distribution_list()
-            .push_front(last_community_distrib);
+        self.update_community_distrib(community_distrib);
         Ok(())
     }
 
+    fn get_open_community_distrib(
+        &self,
+        spread_epoch: u64,
+    ) -> SCResult<CommunityDistribution<Self::BigUint>> {
+        let community_distrib = match self
+            .community_distribution_list()
+            .iter()
+            .find(|community_distrib| community_distrib.spread_epoch == spread_epoch)
+        {
+            Some(community_distrib) => community_distrib,
+            None => return sc_error!("Bad spread epoch"),
+        };
+        require!(!community_distrib.sealed, "Community distribution sealed");
+        require!(
+            self.blockchain().get_block_epoch() < community_distrib.claim_start_epoch,
+            "Community distribution already claimable"
+        );
+        Ok(community_distrib)
+    }
+
+    // The list is kept in chronological order, so the nodes in front of the
+    // updated one are popped and pushed back to keep it in the same position.
+    fn update_community_distrib(&self, community_distrib: CommunityDistribution<Self::BigUint>) {
+        let mut newer_community_distribs = Vec::new();
+        while let Some(front) = self.community_distribution_list().pop_front() {
+            if front.spread_epoch == community_distrib.spread_epoch {
+                break;
+            }
+            newer_community_distribs.push(front);
+        }
+
+        self.community_distribution_list()
+            .push_front(community_distrib);
+        for newer_community_distrib in newer_community_distribs.into_iter().rev() {
+            self.community_distribution_list()
+                .push_front(newer_community_distrib);
+        }
+    }
+
     fn add_user_asset_entry(
         &self,
         user_address: Address,
@@ -277,6 +558,13 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         Ok(())
     }
 
+    fn register_address(&self, address: &Address) {
+        if self.address_registry_index(address).is_empty() {
+            let index = self.address_registry().push(address);
+            self.address_registry_index(address).set(&index);
+        }
+    }
+
     fn calculate_user_assets(
         &self,
         address: &Address,
@@ -287,11 +575,13 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         let mut amounts = Vec::<Self::BigUint>::new();
         let mut milestones = Vec::<Vec<UnlockMilestone>>::new();
 
+        // Claim end epochs never decrease from one round to the next, so the
+        // first expired round ends the search.
         for community_distrib in self
             .community_distribution_list()
             .iter()
-            .take(MAX_CLAIMABLE_DISTRIBUTION_ROUNDS)
-            .filter(|x| x.spread_epoch <= current_epoch)
+            .take_while(|x| current_epoch <= x.claim_end_epoch)
+            .filter(|x| x.claim_start_epoch <= current_epoch)
         {
             let user_asset_key = UserAssetKey {
                 user_address: address.clone(),
@@ -311,12 +601,27 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         (amounts, milestones)
     }
 
-    fn get_biggest_unclaimable_asset_epoch(&self) -> u64 {
+    fn get_expired_distribution_epochs(&self) -> Vec<u64> {
+        let current_epoch = self.blockchain().get_block_epoch();
         self.community_distribution_list()
             .iter()
-            .nth(MAX_CLAIMABLE_DISTRIBUTION_ROUNDS)
+            .skip_while(|community_distrib| current_epoch <= community_distrib.claim_end_epoch)
             .map(|community_distrib| community_distrib.spread_epoch)
-            .unwrap_or_default()
+            .collect()
+    }
+
+    // Expired rounds sit at the back of the list. A round that still has
+    // blocklisted entries is kept, so they can be cleared once unblocked.
+    fn remove_expired_community_distribs(&self, retained_epochs: &[u64]) {
+        let current_epoch = self.blockchain().get_block_epoch();
+        while let Some(community_distrib) = self.community_distribution_list().back() {
+            if current_epoch <= community_distrib.claim_end_epoch
+                || retained_epochs.contains(&community_distrib.spread_epoch)
+            {
+                break;
+            }
+            self.community_distribution_list().pop_back();
+        }
     }
 
     fn remove_asset_entries_between_epochs(&self, lower: u64, higher: u64) -> usize {
@@ -328,23 +633,63 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
             return 0;
         }
 
-        let mut to_remove_keys = Vec::new();
+        let (removed_entries, _, _) = self.remove_asset_entries(
+            |spread_epoch| lower <= spread_epoch && spread_epoch <= higher,
+            false,
+        );
+        removed_entries.len()
+    }
+
+    fn remove_asset_entries<F>(
+        &self,
+        should_remove_epoch: F,
+        keep_blocklisted: bool,
+    ) -> (Vec<(UserAssetKey, Self::BigUint)>, Vec<u64>, bool)
+    where
+        F: Fn(u64) -> bool,
+    {
+        let mut to_remove_entries = Vec::new();
+        let mut retained_epochs = Vec::new();
+        let mut all_entries_visited = true;
         let search_gas_limit = self.blockchain().get_gas_left() / 2;
-        for (user_asset_index, user_asset_key) in self.user_asset_map().keys().enumerate() {
+        for (user_asset_index, (user_asset_key, asset_amount)) in
+            self.user_asset_map().iter().enumerate()
+        {
             if (user_asset_index + 1) % GAS_CHECK_FREQUENCY == 0
                 && self.blockchain().get_gas_left() < search_gas_limit
             {
+                all_entries_visited = false;
                 break;
             }
-            if lower <= user_asset_key.spread_epoch && user_asset_key.spread_epoch <= higher {
-                to_remove_keys.push(user_asset_key);
+            if !should_remove_epoch(user_asset_key.spread_epoch) {
+                continue;
+            }
+            if keep_blocklisted && self.is_blocklisted(&user_asset_key.user_address) {
+                if !retained_epochs.contains(&user_asset_key.spread_epoch) {
+                    retained_epochs.push(user_asset_key.spread_epoch);
+                }
+                continue;
             }
+            to_remove_entries.push((user_asset_key, asset_amount));
         }
 
-        for key in to_remove_keys.iter() {
+        for (key, _) in to_remove_entries.iter() {
             self.user_asset_map().remove(&key);
         }
-        to_remove_keys.len()
+        (to_remove_entries, retained_epochs, all_entries_visited)
+    }
+
+    fn redirect_cleared_assets(&self, amount: &Self::BigUint) {
+        if amount == &0 {
+            return;
+        }
+
+        if self.treasury_address().is_empty() {
+            let new_recoverable_supply = self.recoverable_supply().get() + amount.clone();
+            self.recoverable_supply().set(&new_recoverable_supply);
+        } else {
+            self.mint_and_send_assets(&self.treasury_address().get(), amount);
+        }
     }
 
     fn require_community_distribution_list_not_empty(&self) -> SCResult<()> {
@@ -352,6 +697,14 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
             !self.community_distribution_list().is_empty(),
             "Empty community assets list"
         );
+        self.require_community_distribs_migrated()
+    }
+
+    fn require_community_distribs_migrated(&self) -> SCResult<()> {
+        require!(
+            self.community_distribs_migrated().get(),
+            "Community distributions not migrated"
+        );
         Ok(())
     }
 
@@ -368,9 +721,31 @@ pub trait EsdtDistribution: asset::AssetModule + global_op::GlobalOperationModul
         &self,
     ) -> LinkedListMapper<Self::Storage, CommunityDistribution<Self::BigUint>>;
 
+    #[storage_mapper("community_distribs_migrated")]
+    fn community_distribs_migrated(&self) -> SingleValueMapper<Self::Storage, bool>;
+
     #[storage_mapper("user_asset_map")]
     fn user_asset_map(&self) -> MapMapper<Self::Storage, UserAssetKey, Self::BigUint>;
 
+    #[view(getTreasuryAddress)]
+    #[storage_mapper("treasury_address")]
+    fn treasury_address(&self) -> SingleValueMapper<Self::Storage, Address>;
+
+    #[view(getRecoverableSupply)]
+    #[storage_mapper("recoverable_supply")]
+    fn recoverable_supply(&self) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+
+    #[view(getClearedAmount)]
+    #[storage_mapper("cleared_amount")]
+    fn cleared_amount(&self, spread_epoch: u64) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+
+    #[storage_mapper("address_registry")]
+    fn address_registry(&self) -> VecMapper<Self::Storage, Address>;
+
+    #[view(getAddressRegistryIndex)]
+    #[storage_mapper("address_registry_index")]
+    fn address_registry_index(&self, address: &Address) -> SingleValueMapper<Self::Storage, usize>;
+
     #[storage_mapper("locked_asset_factory_address")]
     fn locked_asset_factory_address(&self) -> SingleValueMapper<Self::Storage, Address>;
 }
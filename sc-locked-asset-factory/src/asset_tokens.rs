@@ -0,0 +1,124 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use distrib_common::*;
+use elrond_wasm::{require, sc_error};
+use modules::*;
+
+/// `Mint` tokens are burned when locked and minted when unlocked, which needs local roles.
+/// `Treasury` tokens are held by the factory while locked and sent back when unlocked.
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum AssetTokenMode {
+    Mint,
+    Treasury,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct AssetTokenConfig {
+    pub mode: AssetTokenMode,
+    pub active: bool,
+}
+
+/// Tokens that can be locked besides the default asset token, which is always in `Mint` mode.
+/// Disabled tokens cannot be locked anymore, but what is locked still unlocks.
+#[elrond_wasm_derive::module]
+pub trait AssetTokensModule: asset::AssetModule {
+    #[endpoint(addAssetToken)]
+    fn add_asset_token(&self, token_id: TokenIdentifier, mode: AssetTokenMode) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(token_id.is_esdt(), "Not an ESDT");
+        require!(
+            token_id != self.asset_token_id().get(),
+            "Default asset token"
+        );
+        require!(
+            !self.asset_tokens().contains_key(&token_id),
+            "Asset token already added"
+        );
+
+        self.asset_tokens()
+            .insert(token_id, AssetTokenConfig { mode, active: true });
+        Ok(())
+    }
+
+    #[endpoint(disableAssetToken)]
+    fn disable_asset_token(&self, token_id: TokenIdentifier) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        let mut config = match self.asset_tokens().get(&token_id) {
+            Some(config) => config,
+            None => return sc_error!("Unknown asset token"),
+        };
+        require!(config.active, "Asset token already disabled");
+
+        config.active = false;
+        self.asset_tokens().insert(token_id, config);
+        Ok(())
+    }
+
+    #[view(getAssetTokens)]
+    fn get_asset_tokens(&self) -> MultiResultVec<MultiResult2<TokenIdentifier, AssetTokenConfig>> {
+        self.asset_tokens()
+            .iter()
+            .map(|(token_id, config)| (token_id, config).into())
+            .collect::<Vec<MultiResult2<TokenIdentifier, AssetTokenConfig>>>()
+            .into()
+    }
+
+    fn require_lockable_asset_token(&self, token_id: &TokenIdentifier) -> SCResult<()> {
+        if *token_id == self.asset_token_id().get() {
+            return Ok(());
+        }
+        match self.asset_tokens().get(token_id) {
+            Some(config) => {
+                require!(config.active, "Asset token disabled");
+                Ok(())
+            }
+            None => sc_error!("Bad payment token"),
+        }
+    }
+
+    fn get_asset_token_id(&self, attributes: &LockedTokenAttributes) -> TokenIdentifier {
+        match &attributes.asset_token_id {
+            Some(asset_token_id) => asset_token_id.clone(),
+            None => self.asset_token_id().get(),
+        }
+    }
+
+    /// The default asset token is left out of the attributes.
+    fn get_attributes_asset_token_id(&self, token_id: &TokenIdentifier) -> Option<TokenIdentifier> {
+        if *token_id == self.asset_token_id().get() {
+            None
+        } else {
+            Some(token_id.clone())
+        }
+    }
+
+    fn is_treasury_asset_token(&self, token_id: &TokenIdentifier) -> bool {
+        match self.asset_tokens().get(token_id) {
+            Some(config) => config.mode == AssetTokenMode::Treasury,
+            None => false,
+        }
+    }
+
+    fn release_asset_tokens(
+        &self,
+        token_id: &TokenIdentifier,
+        address: &Address,
+        amount: &Self::BigUint,
+    ) {
+        if !self.is_treasury_asset_token(token_id) {
+            self.mint_and_send_asset_tokens(token_id, address, amount);
+        } else if amount > &0 {
+            self.send().transfer_tokens(token_id, 0, amount, address);
+        }
+    }
+
+    fn absorb_asset_tokens(&self, token_id: &TokenIdentifier, amount: &Self::BigUint) {
+        if !self.is_treasury_asset_token(token_id) {
+            self.burn_asset_tokens(token_id, amount);
+        }
+    }
+
+    #[storage_mapper("asset_tokens")]
+    fn asset_tokens(&self) -> MapMapper<Self::Storage, TokenIdentifier, AssetTokenConfig>;
+}
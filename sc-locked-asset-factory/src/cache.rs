@@ -10,51 +10,79 @@ use super::locked_asset;
 use distrib_common::*;
 use modules::*;
 
-const AMOUNT_TO_BURN: u64 = 1;
 const GAS_LEFT_THRESHOLD: u64 = 5000000;
+const LEGACY_RETAINED_SFT_AMOUNT: u64 = 1;
+const MAX_CACHED_ATTRIBUTES: usize = 10000;
+
+/// Key of the per-epoch cache used before nonces were cached by attributes hash,
+/// laid out like the attributes of that time.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode)]
+pub struct LegacyCachedAttributes {
+    pub unlock_milestones: Vec<(u64, u8)>,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct CachedSftNonce {
+    pub nonce: Nonce,
+    pub fully_unlocked_epoch: Epoch,
+}
 
+/// Nonces are cached by the hash of their attributes so that identical schedules
+/// share a nonce across epochs. The factory keeps one unit of every cached nonce
+/// to be able to add quantity to it. Once a schedule has fully unlocked, its entry
+/// is evicted by `cleanupUnusedTokens` and the unit is burned. Entries are also indexed
+/// by the epoch they fully unlock in, which eviction walks with a persistent cursor.
 #[elrond_wasm_derive::module]
 pub trait CacheModule: asset::AssetModule + locked_asset::LockedAssetModule {
     fn get_cached_sft_nonce_for_attributes(
         &self,
         attributes: &LockedTokenAttributes,
     ) -> Option<Nonce> {
-        let current_epoch = self.blockchain().get_block_epoch();
-        let cache_epoch = self.cache_epoch().get();
+        let attributes_hash = self.get_attributes_hash(attributes);
+        self.cached_sft_nonces_by_hash()
+            .get(&attributes_hash)
+            .map(|cached_sft_nonce| cached_sft_nonce.nonce)
+    }
 
-        if current_epoch != cache_epoch {
-            self.cache_epoch().set(&current_epoch);
-            self.cached_attributes_to_sft_nonce_map().clear();
-            None
-        } else {
-            self.cached_attributes_to_sft_nonce_map().get(attributes)
+    /// Schedules that have already fully unlocked are not cached, since eviction
+    /// only moves forward and would never reach them.
+    fn cache_attributes_and_nonce(&self, attributes: &LockedTokenAttributes, nonce: Nonce) {
+        if self.is_cache_full() {
+            return;
         }
-    }
 
-    fn cache_attributes_and_nonce(&self, attributes: LockedTokenAttributes, nonce: Nonce) {
-        if self.cached_attributes_to_sft_nonce_map().is_empty() {
-            self.first_cached_sft_nonce().set(&nonce);
+        let fully_unlocked_epoch = attributes.fully_unlocked_epoch();
+        if fully_unlocked_epoch <= self.blockchain().get_block_epoch() {
+            return;
         }
 
-        self.cached_attributes_to_sft_nonce_map()
-            .insert(attributes, nonce);
+        let attributes_hash = self.get_attributes_hash(attributes);
+        let cached_sft_nonce = CachedSftNonce {
+            nonce,
+            fully_unlocked_epoch,
+        };
+        self.cached_sft_nonces_by_hash()
+            .insert(attributes_hash.clone(), cached_sft_nonce);
+        self.cached_sft_nonces().insert(nonce);
+        self.cached_hashes_by_unlock_epoch(fully_unlocked_epoch)
+            .insert(attributes_hash);
+
+        if self.cache_eviction_epoch().is_empty()
+            || fully_unlocked_epoch < self.cache_eviction_epoch().get()
+        {
+            self.cache_eviction_epoch().set(&fully_unlocked_epoch);
+        }
     }
 
     #[endpoint(cleanupUnusedTokens)]
     fn cleanup_unused_tokens(&self) -> SCResult<u64> {
         only_owner!(self, "Permission denied");
+        let mut burned_count = self.evict_fully_unlocked_cache_entries();
+
         let last_burned_sft_nonce_initial = self.last_burned_sft_nonce().get();
         let locked_asset_token_id = self.locked_asset_token_id().get();
         let mut last_burned_sft_nonce = last_burned_sft_nonce_initial;
-        let amount_to_burn = Self::BigUint::from(AMOUNT_TO_BURN);
-        let current_epoch = self.blockchain().get_block_epoch();
-        let cache_epoch = self.cache_epoch().get();
-
-        let limit_nonce_to_burn = if cache_epoch == current_epoch {
-            self.first_cached_sft_nonce().get()
-        } else {
-            self.locked_asset_token_nonce().get() + 1
-        };
+        let limit_nonce_to_burn = self.locked_asset_token_nonce().get() + 1;
 
         for nonce in last_burned_sft_nonce + 1..limit_nonce_to_burn {
             let gas_left = self.blockchain().get_gas_left();
@@ -62,7 +90,11 @@ pub trait CacheModule: asset::AssetModule + locked_asset::LockedAssetModule {
             if gas_left < GAS_LEFT_THRESHOLD {
                 break;
             }
-            self.burn_locked_assets(&locked_asset_token_id, &amount_to_burn, nonce);
+            if !self.cached_sft_nonces().contains(&nonce)
+                && self.burn_retained_sft_amount(&locked_asset_token_id, nonce)
+            {
+                burned_count += 1;
+            }
             last_burned_sft_nonce = nonce;
         }
 
@@ -70,20 +102,140 @@ pub trait CacheModule: asset::AssetModule + locked_asset::LockedAssetModule {
             self.last_burned_sft_nonce().set(&last_burned_sft_nonce);
         }
 
-        Ok(last_burned_sft_nonce - last_burned_sft_nonce_initial)
+        Ok(burned_count)
+    }
+
+    /// Cached nonces are skipped by the cleanup cursor, so their unit is burned on eviction.
+    /// Untracked nonces the cursor has not reached yet are left to the cursor.
+    fn evict_fully_unlocked_cache_entries(&self) -> u64 {
+        if self.cache_eviction_epoch().is_empty() {
+            return 0;
+        }
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let locked_asset_token_id = self.locked_asset_token_id().get();
+        let last_burned_sft_nonce = self.last_burned_sft_nonce().get();
+        let mut eviction_epoch = self.cache_eviction_epoch().get();
+        let mut burned_count = 0u64;
+        while eviction_epoch <= current_epoch {
+            let attributes_hashes = self
+                .cached_hashes_by_unlock_epoch(eviction_epoch)
+                .iter()
+                .collect::<Vec<H256>>();
+            for attributes_hash in attributes_hashes.iter() {
+                if self.blockchain().get_gas_left() < GAS_LEFT_THRESHOLD {
+                    break;
+                }
+                self.cached_hashes_by_unlock_epoch(eviction_epoch)
+                    .remove(attributes_hash);
+                let nonce = match self.cached_sft_nonces_by_hash().remove(attributes_hash) {
+                    Some(cached_sft_nonce) => cached_sft_nonce.nonce,
+                    None => continue,
+                };
+                self.cached_sft_nonces().remove(&nonce);
+                if (self.is_retained_sft_amount_tracked(nonce) || nonce <= last_burned_sft_nonce)
+                    && self.burn_retained_sft_amount(&locked_asset_token_id, nonce)
+                {
+                    burned_count += 1;
+                }
+            }
+
+            if !self
+                .cached_hashes_by_unlock_epoch(eviction_epoch)
+                .is_empty()
+                || self.blockchain().get_gas_left() < GAS_LEFT_THRESHOLD
+            {
+                break;
+            }
+            eviction_epoch += 1;
+        }
+
+        self.cache_eviction_epoch().set(&eviction_epoch);
+        burned_count
     }
 
+    /// Removes the entries of the per-epoch cache used before the upgrade.
+    /// Should be called until it returns 0, the number of entries left.
+    #[endpoint(clearLegacyCache)]
+    fn clear_legacy_cache(&self) -> SCResult<usize> {
+        only_owner!(self, "Permission denied");
+        while let Some(legacy_attributes) = self.cached_attributes_to_sft_nonce_map().keys().next()
+        {
+            if self.blockchain().get_gas_left() < GAS_LEFT_THRESHOLD {
+                return Ok(self.cached_attributes_to_sft_nonce_map().len());
+            }
+            self.cached_attributes_to_sft_nonce_map()
+                .remove(&legacy_attributes);
+        }
+
+        self.cache_epoch().clear();
+        self.first_cached_sft_nonce().clear();
+        Ok(0)
+    }
+
+    /// Nonces created before tracking started are assumed to retain a single unit.
+    fn burn_retained_sft_amount(&self, token_id: &TokenIdentifier, nonce: Nonce) -> bool {
+        let retained_amount = if self.is_retained_sft_amount_tracked(nonce) {
+            let retained_amount = self.retained_sft_amount(nonce).get();
+            self.retained_sft_amount(nonce).clear();
+            retained_amount
+        } else {
+            Self::BigUint::from(LEGACY_RETAINED_SFT_AMOUNT)
+        };
+
+        if retained_amount == 0 {
+            return false;
+        }
+        self.burn_locked_assets(token_id, &retained_amount, nonce);
+        true
+    }
+
+    /// Returns the number of nonces the cleanup cursor has yet to pass
+    /// and the epoch eviction will resume from. Cached nonces fully unlocked
+    /// in an earlier epoch have all been evicted.
+    #[view(getPendingCleanup)]
+    fn get_pending_cleanup(&self) -> MultiResult2<u64, Epoch> {
+        let pending_nonces = self
+            .locked_asset_token_nonce()
+            .get()
+            .saturating_sub(self.last_burned_sft_nonce().get());
+        (pending_nonces, self.cache_eviction_epoch().get()).into()
+    }
+
+    #[view(getCachedAttributesCount)]
+    fn get_cached_attributes_count(&self) -> usize {
+        self.cached_sft_nonces_by_hash().len()
+    }
+
+    /// While full, new schedules get a nonce of their own until eviction makes room.
+    #[view(isCacheFull)]
+    fn is_cache_full(&self) -> bool {
+        self.cached_sft_nonces_by_hash().len() >= MAX_CACHED_ATTRIBUTES
+    }
+
+    #[storage_mapper("cached_sft_nonces_by_hash")]
+    fn cached_sft_nonces_by_hash(&self) -> MapMapper<Self::Storage, H256, CachedSftNonce>;
+
+    #[storage_mapper("cached_sft_nonces")]
+    fn cached_sft_nonces(&self) -> SetMapper<Self::Storage, Nonce>;
+
+    #[storage_mapper("cached_hashes_by_unlock_epoch")]
+    fn cached_hashes_by_unlock_epoch(&self, epoch: Epoch) -> SetMapper<Self::Storage, H256>;
+
+    #[storage_mapper("cache_eviction_epoch")]
+    fn cache_eviction_epoch(&self) -> SingleValueMapper<Self::Storage, Epoch>;
+
+    #[storage_mapper("last_burned_sft_nonce")]
+    fn last_burned_sft_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
+
     #[storage_mapper("cached_attributes_to_sft_nonce_map")]
     fn cached_attributes_to_sft_nonce_map(
         &self,
-    ) -> MapMapper<Self::Storage, LockedTokenAttributes, Nonce>;
+    ) -> MapMapper<Self::Storage, LegacyCachedAttributes, Nonce>;
 
     #[storage_mapper("cache_epoch")]
     fn cache_epoch(&self) -> SingleValueMapper<Self::Storage, Epoch>;
 
-    #[storage_mapper("last_burned_sft_nonce")]
-    fn last_burned_sft_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
-
     #[storage_mapper("first_cached_sft_nonce")]
     fn first_cached_sft_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
 }
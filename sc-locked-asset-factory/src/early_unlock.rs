@@ -0,0 +1,163 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+type Nonce = u64;
+type Epoch = u64;
+
+use super::asset_tokens;
+use super::locked_asset;
+use distrib_common::*;
+use elrond_wasm::{require, sc_error};
+use modules::*;
+
+/// Penalty taken from each locked portion, growing with the epochs left until it unlocks.
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct EarlyUnlockPenalty {
+    pub percent_per_epoch: u64,
+    pub max_percent: u64,
+}
+
+#[elrond_wasm_derive::module]
+pub trait EarlyUnlockModule:
+    asset::AssetModule
+    + locked_asset::LockedAssetModule
+    + blocklist::BlocklistModule
+    + asset_tokens::AssetTokensModule
+{
+    #[endpoint(setEarlyUnlockPenalty)]
+    fn set_early_unlock_penalty(&self, percent_per_epoch: u64, max_percent: u64) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(
+            max_percent <= PERCENTAGE_TOTAL,
+            "Max penalty more than 100%"
+        );
+
+        self.early_unlock_penalty().set(&EarlyUnlockPenalty {
+            percent_per_epoch,
+            max_percent,
+        });
+        Ok(())
+    }
+
+    #[endpoint(disableEarlyUnlock)]
+    fn disable_early_unlock(&self) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.early_unlock_penalty().clear();
+        Ok(())
+    }
+
+    #[endpoint(setPenaltyReceiver)]
+    fn set_penalty_receiver(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.penalty_receiver().set(&address);
+        Ok(())
+    }
+
+    #[endpoint(removePenaltyReceiver)]
+    fn remove_penalty_receiver(&self) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.penalty_receiver().clear();
+        Ok(())
+    }
+
+    #[payable("*")]
+    #[endpoint(unlockEarly)]
+    fn unlock_early(&self) -> SCResult<MultiResult2<Self::BigUint, Self::BigUint>> {
+        let (amount, token_id) = self.call_value().payment_token_pair();
+        let token_nonce = self.call_value().esdt_token_nonce();
+        let locked_token_id = self.locked_asset_token_id().get();
+        require!(token_id == locked_token_id, "Bad payment token");
+        require!(amount > 0, "Zero input amount");
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+
+        let attributes = self.get_attributes(&token_id, token_nonce)?;
+        let (unlock_amount, penalty_amount) =
+            self.get_unlock_early_amounts(&amount, &attributes)?;
+        let asset_token_id = self.get_asset_token_id(&attributes);
+        self.release_asset_tokens(&asset_token_id, &caller, &unlock_amount);
+        self.release_penalty_tokens(&asset_token_id, &penalty_amount);
+
+        self.burn_locked_assets(&locked_token_id, &amount, token_nonce);
+        Ok((unlock_amount, penalty_amount).into())
+    }
+
+    /// Returns the amount the caller would receive and the penalty taken.
+    #[view(getUnlockEarlyQuote)]
+    fn get_unlock_early_quote(
+        &self,
+        token_nonce: Nonce,
+        amount: Self::BigUint,
+    ) -> SCResult<MultiResult2<Self::BigUint, Self::BigUint>> {
+        let attributes = self.get_stored_attributes(token_nonce)?;
+        let (unlock_amount, penalty_amount) =
+            self.get_unlock_early_amounts(&amount, &attributes)?;
+        Ok((unlock_amount, penalty_amount).into())
+    }
+
+    fn get_unlock_early_amounts(
+        &self,
+        amount: &Self::BigUint,
+        attributes: &LockedTokenAttributes,
+    ) -> SCResult<(Self::BigUint, Self::BigUint)> {
+        require!(
+            !self.early_unlock_penalty().is_empty(),
+            "Early unlock not enabled"
+        );
+        require!(
+            attributes.revocable_grant.is_none(),
+            "Revocable locked assets cannot be unlocked early"
+        );
+        let current_epoch = self.blockchain().get_block_epoch();
+        let penalty_amount = self.get_early_unlock_penalty(amount, current_epoch, attributes);
+        Ok((amount.clone() - penalty_amount.clone(), penalty_amount))
+    }
+
+    fn release_penalty_tokens(&self, token_id: &TokenIdentifier, amount: &Self::BigUint) {
+        if !self.penalty_receiver().is_empty() {
+            self.release_asset_tokens(token_id, &self.penalty_receiver().get(), amount);
+        } else if self.is_treasury_asset_token(token_id) && amount > &0 {
+            self.burn_asset_tokens(token_id, amount);
+        }
+    }
+
+    fn get_early_unlock_penalty(
+        &self,
+        amount: &Self::BigUint,
+        current_epoch: Epoch,
+        attributes: &LockedTokenAttributes,
+    ) -> Self::BigUint {
+        let penalty = self.early_unlock_penalty().get();
+        let mut penalty_amount = Self::BigUint::zero();
+
+        for (unlock_epoch, unlock_amount) in self.get_remaining_unlock_amounts(amount, attributes) {
+            // Milestones unlock in the epoch after their unlock epoch.
+            let epochs_left = (unlock_epoch + 1).saturating_sub(current_epoch);
+            let penalty_percent = core::cmp::min(
+                epochs_left.saturating_mul(penalty.percent_per_epoch),
+                penalty.max_percent,
+            );
+            penalty_amount += unlock_amount * Self::BigUint::from(penalty_percent)
+                / Self::BigUint::from(PERCENTAGE_TOTAL);
+        }
+        penalty_amount
+    }
+
+    #[view(getEarlyUnlockPenalty)]
+    fn get_early_unlock_penalty_view(&self) -> OptionalResult<EarlyUnlockPenalty> {
+        if self.early_unlock_penalty().is_empty() {
+            return OptionalResult::None;
+        }
+        OptionalResult::Some(self.early_unlock_penalty().get())
+    }
+
+    #[storage_mapper("early_unlock_penalty")]
+    fn early_unlock_penalty(&self) -> SingleValueMapper<Self::Storage, EarlyUnlockPenalty>;
+
+    /// Receives early unlock penalties and the unvested part of revoked grants.
+    /// While empty, those amounts are not minted, or burned for `Treasury` tokens,
+    /// which needs the local burn role.
+    #[view(getPenaltyReceiver)]
+    #[storage_mapper("penalty_receiver")]
+    fn penalty_receiver(&self) -> SingleValueMapper<Self::Storage, Address>;
+}
@@ -8,12 +8,45 @@ use distrib_common::*;
 use dex_common::*;
 use modules::*;
 
+mod asset_tokens;
 mod cache;
+mod early_unlock;
 mod locked_asset;
+mod mint_quota;
+mod revocation;
+mod schedule_catalogue;
+mod voting_power;
+
+type Nonce = u64;
+type UnlockAssetsResultType<BigUint> =
+    MultiResult2<GenericEsdtAmountPair<BigUint>, GenericEsdtAmountPair<BigUint>>;
+
+const MAX_MERGE_DEPOSITS: usize = 10;
+
+/// `Issued` means the factory roles were not set through `issueNft` or `setTokenRoles`,
+/// though they may have been set with `setLocalRoles`.
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum LockedAssetTokenStatus {
+    NotIssued,
+    IssuePending,
+    Issued,
+    RolesPending,
+    Ready,
+}
 
 #[elrond_wasm_derive::contract]
 pub trait LockedAssetFactory:
-    asset::AssetModule + locked_asset::LockedAssetModule + cache::CacheModule
+    asset::AssetModule
+    + locked_asset::LockedAssetModule
+    + cache::CacheModule
+    + blocklist::BlocklistModule
+    + schedule_catalogue::ScheduleCatalogueModule
+    + mint_quota::MintQuotaModule
+    + early_unlock::EarlyUnlockModule
+    + revocation::RevocationModule
+    + voting_power::VotingPowerModule
+    + asset_tokens::AssetTokensModule
+    + token_metadata::TokenMetadataModule
 {
     #[init]
     fn init(
@@ -54,8 +87,16 @@ pub trait LockedAssetFactory:
         );
         require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
         require!(amount > 0, "Zero input amount");
+        self.consume_mint_quota(&caller, &amount)?;
 
-        Ok(self.produce_tokens_and_send(&amount, &self.create_default_unlock_milestones(), &address))
+        let attributes = LockedTokenAttributes {
+            unlock_milestones: self.create_default_unlock_milestones(),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: None,
+        };
+        Ok(self.produce_tokens_and_send(&amount, &attributes, &address))
     }
 
     #[endpoint]
@@ -73,57 +114,450 @@ pub trait LockedAssetFactory:
         require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
         require!(amount > 0, "Zero input amount");
         require!(!schedule.is_empty(), "Empty param");
+        self.validate_unlock_milestones(&schedule)?;
+        self.consume_mint_quota(&caller, &amount)?;
 
-        let _ = self.produce_tokens_and_send(&amount, &schedule.0, &address);
+        let attributes = LockedTokenAttributes {
+            unlock_milestones: schedule.into_vec(),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: None,
+        };
+        let _ = self.produce_tokens_and_send(&amount, &attributes, &address);
         Ok(())
     }
 
+    #[endpoint]
+    fn createAndForwardWithTemplate(
+        &self,
+        amount: Self::BigUint,
+        address: Address,
+        template_id: u64,
+    ) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.whitelisted_contracts().contains(&caller),
+            "Permission denied"
+        );
+        require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
+        require!(amount > 0, "Zero input amount");
+
+        let unlock_period = self.get_active_unlock_period(template_id)?;
+        self.consume_mint_quota(&caller, &amount)?;
+        self.increase_template_locked_amount(template_id, &self.asset_token_id().get(), &amount);
+
+        let attributes = LockedTokenAttributes {
+            unlock_milestones: self.create_unlock_milestones_from_period(&unlock_period),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: None,
+        };
+        Ok(self.produce_tokens_and_send(&amount, &attributes, &address))
+    }
+
+    /// Locks `amount` for `address` under a grant `admin` can revoke.
+    #[endpoint]
+    fn createAndForwardRevocable(
+        &self,
+        amount: Self::BigUint,
+        address: Address,
+        admin: Address,
+        #[var_args] schedule: VarArgs<UnlockMilestone>,
+    ) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.whitelisted_contracts().contains(&caller),
+            "Permission denied"
+        );
+        require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
+        require!(amount > 0, "Zero input amount");
+        require!(!schedule.is_empty(), "Empty param");
+        self.validate_unlock_milestones(&schedule)?;
+        self.consume_mint_quota(&caller, &amount)?;
+
+        let revocable_grant =
+            self.create_revocable_grant(address.clone(), admin, &amount, &schedule.0);
+        let attributes = LockedTokenAttributes {
+            unlock_milestones: schedule.into_vec(),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: Some(revocable_grant),
+            asset_token_id: None,
+        };
+        Ok(self.produce_tokens_and_send(&amount, &attributes, &address))
+    }
+
+    #[endpoint]
+    fn createAndForwardLinearSchedule(
+        &self,
+        amount: Self::BigUint,
+        address: Address,
+        start_epoch: u64,
+        end_epoch: u64,
+        cliff_epoch: u64,
+    ) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.whitelisted_contracts().contains(&caller),
+            "Permission denied"
+        );
+        require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
+        require!(amount > 0, "Zero input amount");
+
+        let linear_schedule = LinearUnlockSchedule {
+            start_epoch,
+            end_epoch,
+            cliff_epoch,
+        };
+        self.validate_linear_schedule(&linear_schedule)?;
+        self.consume_mint_quota(&caller, &amount)?;
+
+        let attributes = LockedTokenAttributes {
+            unlock_milestones: Vec::new(),
+            linear_schedule: Some(linear_schedule),
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: None,
+        };
+        Ok(self.produce_tokens_and_send(&amount, &attributes, &address))
+    }
+
     #[payable("*")]
     #[endpoint]
     fn unlockAssets(&self) -> SCResult<()> {
+        let caller = self.blockchain().get_caller();
+        let _ = self.unlock_assets_and_send(&caller, None)?;
+        Ok(())
+    }
+
+    /// Unlocks at most `max_amount`. The rest stays locked along the remaining schedule.
+    #[payable("*")]
+    #[endpoint(unlockAssetsPartial)]
+    fn unlock_assets_partial(
+        &self,
+        max_amount: Self::BigUint,
+    ) -> SCResult<UnlockAssetsResultType<Self::BigUint>> {
+        require!(max_amount > 0, "Zero input amount");
+        let caller = self.blockchain().get_caller();
+        let (unlocked_assets, locked_assets) =
+            self.unlock_assets_and_send(&caller, Some(&max_amount))?;
+        Ok((unlocked_assets, locked_assets).into())
+    }
+
+    /// Like `unlockAssets`, but sends everything to `receiver`. Returns the unlocked assets
+    /// and the locked remainder, which has a zero nonce and amount if nothing stays locked.
+    #[payable("*")]
+    #[endpoint(unlockAssetsAndForward)]
+    fn unlock_assets_and_forward(
+        &self,
+        receiver: Address,
+    ) -> SCResult<UnlockAssetsResultType<Self::BigUint>> {
+        let (unlocked_assets, locked_assets) = self.unlock_assets_and_send(&receiver, None)?;
+        Ok((unlocked_assets, locked_assets).into())
+    }
+
+    fn unlock_assets_and_send(
+        &self,
+        receiver: &Address,
+        max_unlock_amount: Option<&Self::BigUint>,
+    ) -> SCResult<(
+        GenericEsdtAmountPair<Self::BigUint>,
+        GenericEsdtAmountPair<Self::BigUint>,
+    )> {
         let (amount, token_id) = self.call_value().payment_token_pair();
         let token_nonce = self.call_value().esdt_token_nonce();
         let locked_token_id = self.locked_asset_token_id().get();
         require!(token_id == locked_token_id, "Bad payment token");
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+        self.require_not_blocklisted(receiver)?;
 
         let attributes = self.get_attributes(&token_id, token_nonce)?;
+        self.require_grant_beneficiary(&attributes, &caller, receiver)?;
+        let is_revoked = self.is_revoked(&attributes);
         let current_block_epoch = self.blockchain().get_block_epoch();
-        let unlock_amount =
-            self.get_unlock_amount(&amount, current_block_epoch, &attributes.unlock_milestones);
-        require!(amount >= unlock_amount, "Cannot unlock more than locked");
-        require!(unlock_amount > 0, "Method called too soon");
+        let vesting_epoch = self.get_vesting_epoch(current_block_epoch, &attributes);
+        let vested_amount = self.get_unlock_amount(&amount, vesting_epoch, &attributes);
+        require!(amount >= vested_amount, "Cannot unlock more than locked");
+        require!(vested_amount > 0 || is_revoked, "Method called too soon");
+        require!(
+            max_unlock_amount.is_none() || !is_revoked,
+            "Revoked grants cannot be unlocked partially"
+        );
 
-        let caller = self.blockchain().get_caller();
-        self.mint_and_send_assets(&caller, &unlock_amount);
+        let unlock_amount = match max_unlock_amount {
+            Some(max_unlock_amount) if max_unlock_amount < &vested_amount => {
+                max_unlock_amount.clone()
+            }
+            _ => vested_amount.clone(),
+        };
+
+        let asset_token_id = self.get_asset_token_id(&attributes);
+        self.release_asset_tokens(&asset_token_id, receiver, &unlock_amount);
+        let unlocked_assets = GenericEsdtAmountPair {
+            token_id: asset_token_id.clone(),
+            token_nonce: 0,
+            amount: unlock_amount.clone(),
+        };
 
+        // The unvested part of revoked grants was already clawed back by `revoke`.
         let locked_remaining = amount.clone() - unlock_amount;
-        if locked_remaining > 0 {
-            let new_unlock_milestones = self
-                .create_new_unlock_milestones(current_block_epoch, &attributes.unlock_milestones);
-            let _ = self.produce_tokens_and_send(&locked_remaining, &new_unlock_milestones, &caller);
-        }
+        let locked_assets = if locked_remaining > 0 && !is_revoked {
+            let new_attributes = if unlock_amount == vested_amount {
+                self.create_new_unlock_attributes(current_block_epoch, &attributes)
+            } else {
+                self.create_partial_unlock_attributes(&amount, &unlock_amount, &attributes)
+            };
+            self.produce_tokens_and_send(&locked_remaining, &new_attributes, receiver)
+        } else {
+            GenericEsdtAmountPair {
+                token_id: locked_token_id.clone(),
+                token_nonce: 0,
+                amount: Self::BigUint::zero(),
+            }
+        };
 
         self.burn_locked_assets(&locked_token_id, &amount, token_nonce);
+        Ok((unlocked_assets, locked_assets))
+    }
+
+    #[payable("*")]
+    #[endpoint(lockAssets)]
+    fn lock_assets(&self, template_id: u64) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+        let unlock_period = self.get_self_lockable_unlock_period(template_id)?;
+        self.lock_payment_and_send(template_id, &unlock_period, &caller)
+    }
+
+    #[payable("*")]
+    #[endpoint]
+    fn createAndForwardWithPayment(
+        &self,
+        address: Address,
+        template_id: u64,
+    ) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.whitelisted_contracts().contains(&caller),
+            "Permission denied"
+        );
+        let unlock_period = self.get_active_unlock_period(template_id)?;
+        self.lock_payment_and_send(template_id, &unlock_period, &address)
+    }
+
+    fn lock_payment_and_send(
+        &self,
+        template_id: u64,
+        unlock_period: &[UnlockMilestone],
+        address: &Address,
+    ) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let (amount, token_id) = self.call_value().payment_token_pair();
+        self.require_lockable_asset_token(&token_id)?;
+        require!(amount > 0, "Zero input amount");
+        require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
+
+        self.absorb_asset_tokens(&token_id, &amount);
+        self.increase_template_locked_amount(template_id, &token_id, &amount);
+
+        let attributes = LockedTokenAttributes {
+            unlock_milestones: self.create_unlock_milestones_from_period(unlock_period),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: self.get_attributes_asset_token_id(&token_id),
+        };
+        Ok(self.produce_tokens_and_send(&amount, &attributes, address))
+    }
+
+    /// Moves locked assets to a catalogue schedule starting now,
+    /// as long as it never unlocks anything earlier than their current one.
+    #[payable("*")]
+    #[endpoint(extendLock)]
+    fn extend_lock(&self, template_id: u64) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let (amount, token_id) = self.call_value().payment_token_pair();
+        let token_nonce = self.call_value().esdt_token_nonce();
+        require!(
+            token_id == self.locked_asset_token_id().get(),
+            "Bad payment token"
+        );
+        require!(amount > 0, "Zero input amount");
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+
+        let attributes = self.get_attributes(&token_id, token_nonce)?;
+        require!(
+            attributes.revocable_grant.is_none(),
+            "Revocable locked assets cannot be extended"
+        );
+        let unlock_period = self.get_active_unlock_period(template_id)?;
+        let new_attributes = LockedTokenAttributes {
+            unlock_milestones: self.create_unlock_milestones_from_period(&unlock_period),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: attributes.asset_token_id.clone(),
+        };
+        require!(
+            self.unlocks_no_earlier(&amount, &attributes, &new_attributes),
+            "New schedule unlocks earlier"
+        );
+        let asset_token_id = self.get_asset_token_id(&attributes);
+        self.increase_template_locked_amount(template_id, &asset_token_id, &amount);
+
+        let new_tokens = self.produce_tokens_and_send(&amount, &new_attributes, &caller);
+        self.burn_locked_assets(&token_id, &amount, token_nonce);
+        self.extend_lock_event(
+            &caller,
+            template_id,
+            token_nonce,
+            new_tokens.token_nonce,
+            &amount,
+        );
+        Ok(new_tokens)
+    }
+
+    #[payable("*")]
+    #[endpoint(depositLockedAssetsForMerge)]
+    fn deposit_locked_assets_for_merge(&self) -> SCResult<()> {
+        let (amount, token_id) = self.call_value().payment_token_pair();
+        let token_nonce = self.call_value().esdt_token_nonce();
+        require!(
+            token_id == self.locked_asset_token_id().get(),
+            "Bad payment token"
+        );
+        require!(amount > 0, "Zero input amount");
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+
+        let attributes = self.get_attributes(&token_id, token_nonce)?;
+        require!(
+            attributes.revocable_grant.is_none(),
+            "Revocable locked assets cannot be merged"
+        );
+
+        let new_amount = match self.merge_deposits(&caller).get(&token_nonce) {
+            Some(deposited_amount) => deposited_amount + amount,
+            None => {
+                require!(
+                    self.merge_deposits(&caller).len() < MAX_MERGE_DEPOSITS,
+                    "Too many deposits"
+                );
+                amount
+            }
+        };
+        self.merge_deposits(&caller).insert(token_nonce, new_amount);
         Ok(())
     }
 
+    #[endpoint(reclaimMergeDeposits)]
+    fn reclaim_merge_deposits(&self) -> SCResult<()> {
+        let caller = self.blockchain().get_caller();
+        let locked_token_id = self.locked_asset_token_id().get();
+        for (token_nonce, amount) in self.merge_deposits(&caller).iter() {
+            self.send()
+                .transfer_tokens(&locked_token_id, token_nonce, &amount, &caller);
+        }
+        self.merge_deposits(&caller).clear();
+        Ok(())
+    }
+
+    #[endpoint(mergeLockedAssets)]
+    fn merge_locked_assets(&self) -> SCResult<GenericEsdtAmountPair<Self::BigUint>> {
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+        require!(self.merge_deposits(&caller).len() > 1, "Nothing to merge");
+
+        let locked_token_id = self.locked_asset_token_id().get();
+        let mut asset_token_id = None;
+        let mut total_amount = Self::BigUint::zero();
+        let mut unlock_amounts = Vec::new();
+        for (token_nonce, amount) in self.merge_deposits(&caller).iter() {
+            let attributes = self.get_attributes(&locked_token_id, token_nonce)?;
+            let deposit_asset_token_id = self.get_asset_token_id(&attributes);
+            match &asset_token_id {
+                Some(asset_token_id) => require!(
+                    *asset_token_id == deposit_asset_token_id,
+                    "Cannot merge different asset tokens"
+                ),
+                None => asset_token_id = Some(deposit_asset_token_id),
+            }
+            unlock_amounts.extend(self.get_remaining_unlock_amounts(&amount, &attributes));
+            self.burn_locked_assets(&locked_token_id, &amount, token_nonce);
+            total_amount += amount;
+        }
+        self.merge_deposits(&caller).clear();
+
+        let unlock_milestones =
+            self.create_merged_unlock_milestones(&unlock_amounts, &total_amount);
+        require!(!unlock_milestones.is_empty(), "Nothing left to unlock");
+        let attributes = LockedTokenAttributes {
+            unlock_milestones,
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: asset_token_id
+                .and_then(|asset_token_id| self.get_attributes_asset_token_id(&asset_token_id)),
+        };
+        Ok(self.produce_tokens_and_send(&total_amount, &attributes, &caller))
+    }
+
+    #[view(getMergeDeposits)]
+    fn get_merge_deposits(
+        &self,
+        address: Address,
+    ) -> MultiResultVec<MultiResult2<Nonce, Self::BigUint>> {
+        self.merge_deposits(&address)
+            .iter()
+            .map(|(token_nonce, amount)| (token_nonce, amount).into())
+            .collect::<Vec<MultiResult2<Nonce, Self::BigUint>>>()
+            .into()
+    }
+
+    #[view(getUnlockableAmount)]
+    fn get_unlockable_amount(
+        &self,
+        token_nonce: Nonce,
+        amount: Self::BigUint,
+        epoch: u64,
+    ) -> SCResult<Self::BigUint> {
+        let attributes = self.get_stored_attributes(token_nonce)?;
+        let vesting_epoch = self.get_vesting_epoch(epoch, &attributes);
+        Ok(self.get_unlock_amount(&amount, vesting_epoch, &attributes))
+    }
+
+    #[view(getUnlockSchedule)]
+    fn get_unlock_schedule(&self, token_nonce: Nonce) -> SCResult<LockedTokenAttributes> {
+        self.get_stored_attributes(token_nonce)
+    }
+
+    /// The attributes `unlockAssets` would give the locked remainder when called in `epoch`.
+    #[view(getRemainingScheduleAfterUnlock)]
+    fn get_remaining_schedule_after_unlock(
+        &self,
+        token_nonce: Nonce,
+        epoch: u64,
+    ) -> SCResult<LockedTokenAttributes> {
+        let attributes = self.get_stored_attributes(token_nonce)?;
+        Ok(self.create_new_unlock_attributes(epoch, &attributes))
+    }
+
     fn produce_tokens_and_send(
         &self,
         amount: &Self::BigUint,
-        unlock_milestones: &[UnlockMilestone],
+        attributes: &LockedTokenAttributes,
         address: &Address,
     ) -> GenericEsdtAmountPair<Self::BigUint> {
-        let attributes = LockedTokenAttributes {
-            unlock_milestones: unlock_milestones.to_vec(),
-        };
-        let result = self.get_cached_sft_nonce_for_attributes(&attributes);
+        let result = self.get_cached_sft_nonce_for_attributes(attributes);
         let sent_nonce = match result {
             Option::Some(cached_nonce) => {
                 self.add_quantity_and_send_locked_assets(&amount, cached_nonce, &address);
                 cached_nonce
             }
             Option::None => {
-                let new_nonce = self.create_and_send_locked_assets(&amount, &attributes, &address);
+                let new_nonce = self.create_and_send_locked_assets(&amount, attributes, &address);
                 self.cache_attributes_and_nonce(attributes, new_nonce);
                 new_nonce
             }
@@ -135,6 +569,7 @@ pub trait LockedAssetFactory:
         }
     }
 
+    /// With `set_roles`, the roles the factory needs are requested once the token is issued.
     #[payable("EGLD")]
     #[endpoint(issueNft)]
     fn issue_nft(
@@ -142,12 +577,19 @@ pub trait LockedAssetFactory:
         token_display_name: BoxedBytes,
         token_ticker: BoxedBytes,
         #[payment] issue_cost: Self::BigUint,
+        #[var_args] set_roles: OptionalArg<bool>,
     ) -> SCResult<AsyncCall<Self::SendApi>> {
         only_owner!(self, "Permission denied");
         require!(
             self.locked_asset_token_id().is_empty(),
             "NFT already issued"
         );
+        require!(!self.issue_pending().get(), "Issue already pending");
+        self.issue_pending().set(&true);
+        let set_roles = match set_roles {
+            OptionalArg::Some(set_roles) => set_roles,
+            OptionalArg::None => false,
+        };
 
         Ok(ESDTSystemSmartContractProxy::new_proxy_obj(self.send())
             .issue_semi_fungible(
@@ -164,14 +606,22 @@ pub trait LockedAssetFactory:
                 },
             )
             .async_call()
-            .with_callback(self.callbacks().issue_nft_callback()))
+            .with_callback(self.callbacks().issue_nft_callback(set_roles)))
     }
 
     #[callback]
-    fn issue_nft_callback(&self, #[call_result] result: AsyncCallResult<TokenIdentifier>) {
+    fn issue_nft_callback(
+        &self,
+        set_roles: bool,
+        #[call_result] result: AsyncCallResult<TokenIdentifier>,
+    ) -> OptionalResult<AsyncCall<Self::SendApi>> {
+        self.issue_pending().clear();
         match result {
             AsyncCallResult::Ok(token_id) => {
                 self.locked_asset_token_id().set(&token_id);
+                if set_roles {
+                    return OptionalResult::Some(self.set_token_roles_async_call(&token_id));
+                }
             }
             AsyncCallResult::Err(_) => {
                 // return payment to initial caller, which can only be the owner
@@ -184,6 +634,59 @@ pub trait LockedAssetFactory:
                 );
             }
         };
+        OptionalResult::None
+    }
+
+    /// Requests the roles the factory needs for the issued token. Can be retried if it failed.
+    #[endpoint(setTokenRoles)]
+    fn set_token_roles(&self) -> SCResult<AsyncCall<Self::SendApi>> {
+        only_owner!(self, "Permission denied");
+        require!(!self.locked_asset_token_id().is_empty(), "No SFT issued");
+        require!(!self.roles_pending().get(), "Roles already pending");
+
+        Ok(self.set_token_roles_async_call(&self.locked_asset_token_id().get()))
+    }
+
+    fn set_token_roles_async_call(&self, token_id: &TokenIdentifier) -> AsyncCall<Self::SendApi> {
+        self.roles_pending().set(&true);
+        ESDTSystemSmartContractProxy::new_proxy_obj(self.send())
+            .set_special_roles(
+                &self.blockchain().get_sc_address(),
+                token_id.as_esdt_identifier(),
+                &[
+                    EsdtLocalRole::NftCreate,
+                    EsdtLocalRole::NftAddQuantity,
+                    EsdtLocalRole::NftBurn,
+                ],
+            )
+            .async_call()
+            .with_callback(self.callbacks().set_token_roles_callback())
+    }
+
+    /// A failed request leaves the roles as they were, so `roles_set` is kept.
+    #[callback]
+    fn set_token_roles_callback(&self, #[call_result] result: AsyncCallResult<()>) {
+        self.roles_pending().clear();
+        if let AsyncCallResult::Ok(()) = result {
+            self.roles_set().set(&true);
+        }
+    }
+
+    #[view(getLockedAssetTokenStatus)]
+    fn get_locked_asset_token_status(&self) -> LockedAssetTokenStatus {
+        if self.locked_asset_token_id().is_empty() {
+            if self.issue_pending().get() {
+                LockedAssetTokenStatus::IssuePending
+            } else {
+                LockedAssetTokenStatus::NotIssued
+            }
+        } else if self.roles_set().get() {
+            LockedAssetTokenStatus::Ready
+        } else if self.roles_pending().get() {
+            LockedAssetTokenStatus::RolesPending
+        } else {
+            LockedAssetTokenStatus::Issued
+        }
     }
 
     #[endpoint(setLocalRoles)]
@@ -202,11 +705,40 @@ pub trait LockedAssetFactory:
             .async_call())
     }
 
+    /// The factory keeps a unit of every nonce it creates, so it can read their data.
+    #[endpoint(resyncLockedAssetTokenNonce)]
+    fn resync_locked_asset_token_nonce(&self, token_nonce: Nonce) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(
+            token_nonce >= self.last_burned_sft_nonce().get(),
+            "Nonce already burned"
+        );
+        let sc_address = self.blockchain().get_sc_address();
+        let token_info = self.blockchain().get_esdt_token_data(
+            &sc_address,
+            self.locked_asset_token_id().get().as_esdt_identifier(),
+            token_nonce,
+        );
+        require!(
+            token_info.amount > 0 && token_info.creator == sc_address,
+            "Nonce not created by this contract"
+        );
+
+        self.locked_asset_token_nonce().set(&token_nonce);
+        Ok(())
+    }
+
     fn create_default_unlock_milestones(&self) -> Vec<UnlockMilestone> {
+        self.create_unlock_milestones_from_period(&self.default_unlock_period().get())
+    }
+
+    fn create_unlock_milestones_from_period(
+        &self,
+        unlock_period: &[UnlockMilestone],
+    ) -> Vec<UnlockMilestone> {
         let current_epoch = self.blockchain().get_block_epoch();
 
-        self.default_unlock_period()
-            .get()
+        unlock_period
             .iter()
             .map(|x| UnlockMilestone {
                 unlock_epoch: x.unlock_epoch + current_epoch,
@@ -215,9 +747,31 @@ pub trait LockedAssetFactory:
             .collect()
     }
 
+    #[event("extend_lock")]
+    fn extend_lock_event(
+        &self,
+        #[indexed] caller: &Address,
+        #[indexed] template_id: u64,
+        #[indexed] old_nonce: Nonce,
+        #[indexed] new_nonce: Nonce,
+        amount: &Self::BigUint,
+    );
+
+    #[storage_mapper("issue_pending")]
+    fn issue_pending(&self) -> SingleValueMapper<Self::Storage, bool>;
+
+    #[storage_mapper("roles_pending")]
+    fn roles_pending(&self) -> SingleValueMapper<Self::Storage, bool>;
+
+    #[storage_mapper("roles_set")]
+    fn roles_set(&self) -> SingleValueMapper<Self::Storage, bool>;
+
     #[storage_mapper("whitelist")]
     fn whitelisted_contracts(&self) -> SetMapper<Self::Storage, Address>;
 
+    #[storage_mapper("merge_deposits")]
+    fn merge_deposits(&self, address: &Address) -> MapMapper<Self::Storage, Nonce, Self::BigUint>;
+
     #[storage_mapper("default_unlock_period")]
     fn default_unlock_period(&self) -> SingleValueMapper<Self::Storage, Vec<UnlockMilestone>>;
 }
@@ -13,9 +13,10 @@ use modules::*;
 const ADDITIONAL_AMOUNT_TO_CREATE: u64 = 1;
 const BURN_TOKENS_GAS_LIMIT: u64 = 5000000;
 const ADD_QUANTITY_GAS_LIMIT: u64 = 5000000;
+const LINEAR_SCHEDULE_SAMPLES: u64 = 12;
 
 #[elrond_wasm_derive::module]
-pub trait LockedAssetModule: asset::AssetModule {
+pub trait LockedAssetModule: asset::AssetModule + token_metadata::TokenMetadataModule {
     fn create_and_send_locked_assets(
         &self,
         amount: &Self::BigUint,
@@ -23,11 +24,10 @@ pub trait LockedAssetModule: asset::AssetModule {
         address: &Address,
     ) -> Nonce {
         let token_id = self.locked_asset_token_id().get();
-        self.create_tokens(&token_id, amount, attributes);
-        let last_created_nonce = self.locked_asset_token_nonce().get();
+        let new_nonce = self.create_tokens(&token_id, amount, attributes);
         self.send()
-            .transfer_tokens(&token_id, last_created_nonce, amount, address);
-        last_created_nonce
+            .transfer_tokens(&token_id, new_nonce, amount, address);
+        new_nonce
     }
 
     fn add_quantity_and_send_locked_assets(
@@ -49,6 +49,10 @@ pub trait LockedAssetModule: asset::AssetModule {
             nonce,
             amount,
         );
+        if !self.locked_asset_supply(nonce).is_empty() {
+            let new_supply = self.locked_asset_supply(nonce).get() + amount.clone();
+            self.locked_asset_supply(nonce).set(&new_supply);
+        }
     }
 
     fn create_tokens(
@@ -56,24 +60,65 @@ pub trait LockedAssetModule: asset::AssetModule {
         token: &TokenIdentifier,
         amount: &Self::BigUint,
         attributes: &LockedTokenAttributes,
-    ) {
+    ) -> Nonce {
         let amount_to_create = amount + &Self::BigUint::from(ADDITIONAL_AMOUNT_TO_CREATE);
-        self.send().esdt_nft_create::<LockedTokenAttributes>(
+        let asset_token_id = match &attributes.asset_token_id {
+            Some(asset_token_id) => asset_token_id.clone(),
+            None => self.asset_token_id().get(),
+        };
+        let attributes_hash = self.get_attributes_hash(attributes);
+        let new_nonce = self.send().esdt_nft_create::<LockedTokenAttributes>(
             self.blockchain().get_gas_left(),
             token.as_esdt_identifier(),
             &amount_to_create,
-            &BoxedBytes::empty(),
+            &attributes.token_name(&asset_token_id),
             &Self::BigUint::zero(),
-            &H256::zero(),
+            &attributes_hash,
             attributes,
-            &[BoxedBytes::empty()],
+            self.get_token_uris(&attributes_hash).as_slice(),
         );
-        self.increase_nonce();
+        self.locked_asset_token_nonce().set(&new_nonce);
+        self.locked_asset_attributes(new_nonce).set(attributes);
+        self.locked_asset_supply(new_nonce).set(&amount_to_create);
+
+        if self.first_tracked_sft_nonce().is_empty() {
+            self.first_tracked_sft_nonce().set(&new_nonce);
+        }
+        self.retained_sft_amount(new_nonce)
+            .set(&Self::BigUint::from(ADDITIONAL_AMOUNT_TO_CREATE));
+        new_nonce
+    }
+
+    fn get_attributes_hash(&self, attributes: &LockedTokenAttributes) -> H256 {
+        let encoded_attributes =
+            elrond_wasm::elrond_codec::top_encode_to_vec(attributes).unwrap_or_default();
+        self.hash_token_attributes(encoded_attributes.as_slice())
+    }
+
+    fn is_retained_sft_amount_tracked(&self, nonce: Nonce) -> bool {
+        !self.first_tracked_sft_nonce().is_empty() && nonce >= self.first_tracked_sft_nonce().get()
     }
 
     fn burn_locked_assets(&self, token_id: &TokenIdentifier, amount: &Self::BigUint, nonce: Nonce) {
         self.send()
             .burn_tokens(token_id, nonce, amount, BURN_TOKENS_GAS_LIMIT);
+        self.decrease_locked_asset_supply(nonce, amount);
+    }
+
+    /// The stored attributes are dropped together with the last unit of the nonce.
+    fn decrease_locked_asset_supply(&self, nonce: Nonce, amount: &Self::BigUint) {
+        if self.locked_asset_supply(nonce).is_empty() {
+            return;
+        }
+
+        let supply = self.locked_asset_supply(nonce).get();
+        if &supply <= amount {
+            self.locked_asset_supply(nonce).clear();
+            self.locked_asset_attributes(nonce).clear();
+        } else {
+            let new_supply = supply - amount.clone();
+            self.locked_asset_supply(nonce).set(&new_supply);
+        }
     }
 
     fn get_attributes(
@@ -96,23 +141,46 @@ pub trait LockedAssetModule: asset::AssetModule {
         }
     }
 
+    /// For views, which cannot rely on the factory still holding a unit of the nonce.
+    /// Nonces created before attributes were stored fall back to the retained unit.
+    fn get_stored_attributes(&self, token_nonce: Nonce) -> SCResult<LockedTokenAttributes> {
+        if self.locked_asset_attributes(token_nonce).is_empty() {
+            self.get_attributes(&self.locked_asset_token_id().get(), token_nonce)
+        } else {
+            Ok(self.locked_asset_attributes(token_nonce).get())
+        }
+    }
+
     fn get_unlock_amount(
         &self,
         amount: &Self::BigUint,
         current_epoch: Epoch,
-        unlock_milestones: &[UnlockMilestone],
+        attributes: &LockedTokenAttributes,
     ) -> Self::BigUint {
-        amount
-            * &Self::BigUint::from(self.get_unlock_percent(current_epoch, unlock_milestones) as u64)
-            / Self::BigUint::from(100u64)
+        let unlock_percent = self.get_unlock_percent(current_epoch, attributes);
+        if unlock_percent <= attributes.unlocked_percent {
+            return Self::BigUint::zero();
+        }
+
+        amount * &Self::BigUint::from(unlock_percent - attributes.unlocked_percent)
+            / Self::BigUint::from(PERCENTAGE_TOTAL - attributes.unlocked_percent)
+    }
+
+    fn get_unlock_percent(&self, current_epoch: Epoch, attributes: &LockedTokenAttributes) -> u64 {
+        match &attributes.linear_schedule {
+            Some(linear_schedule) => self.get_linear_unlock_percent(current_epoch, linear_schedule),
+            None => {
+                self.get_milestones_unlock_percent(current_epoch, &attributes.unlock_milestones)
+            }
+        }
     }
 
-    fn get_unlock_percent(
+    fn get_milestones_unlock_percent(
         &self,
         current_epoch: Epoch,
         unlock_milestones: &[UnlockMilestone],
-    ) -> u8 {
-        let mut unlock_percent = 0u8;
+    ) -> u64 {
+        let mut unlock_percent = 0u64;
 
         for milestone in unlock_milestones {
             if milestone.unlock_epoch < current_epoch {
@@ -122,49 +190,196 @@ pub trait LockedAssetModule: asset::AssetModule {
         unlock_percent
     }
 
-    fn create_new_unlock_milestones(
+    fn get_linear_unlock_percent(
         &self,
         current_epoch: Epoch,
-        old_unlock_milestones: &[UnlockMilestone],
-    ) -> Vec<UnlockMilestone> {
-        let mut new_unlock_milestones = Vec::<UnlockMilestone>::new();
-        let unlock_percent = self.get_unlock_percent(current_epoch, old_unlock_milestones);
-        let unlock_percent_remaining = 100u64 - (unlock_percent as u64);
+        linear_schedule: &LinearUnlockSchedule,
+    ) -> u64 {
+        if current_epoch < linear_schedule.cliff_epoch
+            || current_epoch <= linear_schedule.start_epoch
+        {
+            return 0;
+        }
+        if current_epoch >= linear_schedule.end_epoch {
+            return PERCENTAGE_TOTAL;
+        }
+
+        (current_epoch - linear_schedule.start_epoch) * PERCENTAGE_TOTAL
+            / (linear_schedule.end_epoch - linear_schedule.start_epoch)
+    }
+
+    /// The remaining amount keeps the original schedule. Only the share released so far moves,
+    /// so unlocking often or once ends up releasing the same amounts along the same curve.
+    fn create_new_unlock_attributes(
+        &self,
+        current_epoch: Epoch,
+        old_attributes: &LockedTokenAttributes,
+    ) -> LockedTokenAttributes {
+        let unlock_percent = self.get_unlock_percent(current_epoch, old_attributes);
+        LockedTokenAttributes {
+            unlock_milestones: old_attributes.unlock_milestones.clone(),
+            linear_schedule: old_attributes.linear_schedule,
+            unlocked_percent: core::cmp::max(unlock_percent, old_attributes.unlocked_percent),
+            revocable_grant: old_attributes.revocable_grant.clone(),
+            asset_token_id: old_attributes.asset_token_id.clone(),
+        }
+    }
+
+    /// Attributes for what remains of `amount` after releasing only `unlocked_amount`.
+    /// `unlocked_percent` is chosen so that the remainder is the same share of the
+    /// original amount, rounded up so the remainder never unlocks faster.
+    /// A dust remainder keeps at least the last thousandth of a percent to unlock.
+    fn create_partial_unlock_attributes(
+        &self,
+        amount: &Self::BigUint,
+        unlocked_amount: &Self::BigUint,
+        old_attributes: &LockedTokenAttributes,
+    ) -> LockedTokenAttributes {
+        let locked_remaining = amount - unlocked_amount;
+        let remaining_percent = (locked_remaining
+            * Self::BigUint::from(PERCENTAGE_TOTAL - old_attributes.unlocked_percent)
+            / amount.clone())
+        .to_u64()
+        .unwrap_or_default();
 
-        if unlock_percent_remaining == 0 {
-            return new_unlock_milestones;
+        LockedTokenAttributes {
+            unlock_milestones: old_attributes.unlock_milestones.clone(),
+            linear_schedule: old_attributes.linear_schedule,
+            unlocked_percent: PERCENTAGE_TOTAL - core::cmp::max(remaining_percent, 1),
+            revocable_grant: old_attributes.revocable_grant.clone(),
+            asset_token_id: old_attributes.asset_token_id.clone(),
         }
+    }
+
+    /// The schedule as milestones. A linear schedule is sampled so that
+    /// the milestones never unlock faster than the linear curve would.
+    fn get_unlock_milestones(&self, attributes: &LockedTokenAttributes) -> Vec<UnlockMilestone> {
+        let linear_schedule = match &attributes.linear_schedule {
+            Some(linear_schedule) => linear_schedule,
+            None => return attributes.unlock_milestones.clone(),
+        };
 
-        for old_milestone in old_unlock_milestones.iter() {
-            if old_milestone.unlock_epoch >= current_epoch {
-                let new_unlock_percent: u64 =
-                    (old_milestone.unlock_percent as u64) * 100u64 / unlock_percent_remaining;
-                new_unlock_milestones.push(UnlockMilestone {
-                    unlock_epoch: old_milestone.unlock_epoch,
-                    unlock_percent: new_unlock_percent as u8,
+        let mut unlock_milestones = Vec::<UnlockMilestone>::new();
+        let duration = linear_schedule.end_epoch - linear_schedule.start_epoch;
+        let mut last_unlock_percent = 0u64;
+        for sample in 1..=LINEAR_SCHEDULE_SAMPLES {
+            let unlock_epoch =
+                linear_schedule.start_epoch + duration * sample / LINEAR_SCHEDULE_SAMPLES;
+            let unlock_percent = self.get_linear_unlock_percent(unlock_epoch, linear_schedule);
+            if unlock_percent > last_unlock_percent {
+                unlock_milestones.push(UnlockMilestone {
+                    unlock_epoch,
+                    unlock_percent: unlock_percent - last_unlock_percent,
                 });
+                last_unlock_percent = unlock_percent;
             }
         }
-        let mut sum_of_new_percents = 0u8;
+        unlock_milestones
+    }
 
-        for new_milestone in new_unlock_milestones.iter() {
-            sum_of_new_percents += new_milestone.unlock_percent;
+    /// Amounts that `amount` locked tokens still unlock, by milestone epoch.
+    fn get_remaining_unlock_amounts(
+        &self,
+        amount: &Self::BigUint,
+        attributes: &LockedTokenAttributes,
+    ) -> Vec<(Epoch, Self::BigUint)> {
+        let mut unlock_amounts = Vec::new();
+        let remaining_percent = PERCENTAGE_TOTAL - attributes.unlocked_percent;
+        if remaining_percent == 0 {
+            return unlock_amounts;
+        }
+
+        let mut cumulated_percent = 0u64;
+        for milestone in self.get_unlock_milestones(attributes).iter() {
+            let previous_percent = core::cmp::max(cumulated_percent, attributes.unlocked_percent);
+            cumulated_percent += milestone.unlock_percent;
+            if cumulated_percent <= previous_percent {
+                continue;
+            }
+
+            let unlock_amount = amount * &Self::BigUint::from(cumulated_percent - previous_percent)
+                / Self::BigUint::from(remaining_percent);
+            if unlock_amount > 0 {
+                unlock_amounts.push((milestone.unlock_epoch, unlock_amount));
+            }
         }
-        new_unlock_milestones[0].unlock_percent += 100 - sum_of_new_percents;
-        new_unlock_milestones
+        unlock_amounts
     }
 
-    fn increase_nonce(&self) -> Nonce {
-        let new_nonce = self.locked_asset_token_nonce().get() + 1;
-        self.locked_asset_token_nonce().set(&new_nonce);
-        new_nonce
+    /// Whether `amount` never has more unlocked by any epoch under `new_attributes`
+    /// than under `old_attributes`.
+    fn unlocks_no_earlier(
+        &self,
+        amount: &Self::BigUint,
+        old_attributes: &LockedTokenAttributes,
+        new_attributes: &LockedTokenAttributes,
+    ) -> bool {
+        let old_unlock_amounts = self.get_remaining_unlock_amounts(amount, old_attributes);
+        let new_unlock_amounts = self.get_remaining_unlock_amounts(amount, new_attributes);
+        let mut new_cumulated_amount = Self::BigUint::zero();
+
+        for (unlock_epoch, unlock_amount) in new_unlock_amounts.iter() {
+            new_cumulated_amount += unlock_amount;
+            let mut old_cumulated_amount = Self::BigUint::zero();
+            for (old_unlock_epoch, old_unlock_amount) in old_unlock_amounts.iter() {
+                if old_unlock_epoch <= unlock_epoch {
+                    old_cumulated_amount += old_unlock_amount;
+                }
+            }
+            if new_cumulated_amount > old_cumulated_amount {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Combines amounts unlocking at given epochs into a single schedule for `total_amount`.
+    /// Percents are rounded down and the rest goes to the last milestone,
+    /// so the result never unlocks faster than its inputs.
+    fn create_merged_unlock_milestones(
+        &self,
+        unlock_amounts: &[(Epoch, Self::BigUint)],
+        total_amount: &Self::BigUint,
+    ) -> Vec<UnlockMilestone> {
+        let mut merged_amounts = Vec::<(Epoch, Self::BigUint)>::new();
+        for (unlock_epoch, unlock_amount) in unlock_amounts.iter() {
+            let position = merged_amounts
+                .iter()
+                .position(|(merged_epoch, _)| merged_epoch >= unlock_epoch)
+                .unwrap_or_else(|| merged_amounts.len());
+            if position < merged_amounts.len() && merged_amounts[position].0 == *unlock_epoch {
+                merged_amounts[position].1 += unlock_amount;
+            } else {
+                merged_amounts.insert(position, (*unlock_epoch, unlock_amount.clone()));
+            }
+        }
+
+        let mut unlock_milestones = Vec::<UnlockMilestone>::new();
+        let mut percents_sum = 0u64;
+        for (unlock_epoch, unlock_amount) in merged_amounts.iter() {
+            let unlock_percent = (unlock_amount * &Self::BigUint::from(PERCENTAGE_TOTAL)
+                / total_amount.clone())
+            .to_u64()
+            .unwrap_or_default();
+            unlock_milestones.push(UnlockMilestone {
+                unlock_epoch: *unlock_epoch,
+                unlock_percent,
+            });
+            percents_sum += unlock_percent;
+        }
+
+        if let Some(last_milestone) = unlock_milestones.last_mut() {
+            last_milestone.unlock_percent += PERCENTAGE_TOTAL - percents_sum;
+        }
+        unlock_milestones.retain(|milestone| milestone.unlock_percent != 0);
+        unlock_milestones
     }
 
     fn validate_unlock_milestones(
         &self,
         unlock_milestones: &VarArgs<UnlockMilestone>,
     ) -> SCResult<()> {
-        let mut percents_sum: u8 = 0;
+        let mut percents_sum: u64 = 0;
         let mut last_milestone_unlock_epoch: u64 = 0;
 
         for milestone in unlock_milestones.0.clone() {
@@ -173,22 +388,57 @@ pub trait LockedAssetModule: asset::AssetModule {
                 "Unlock epochs not in order"
             );
             require!(
-                milestone.unlock_percent <= 100,
-                "Unlock percent more than 100"
+                milestone.unlock_percent <= PERCENTAGE_TOTAL,
+                "Unlock percent more than 100%"
             );
             last_milestone_unlock_epoch = milestone.unlock_epoch;
             percents_sum += milestone.unlock_percent;
         }
 
         if !unlock_milestones.is_empty() {
-            require!(percents_sum == 100, "Percents do not sum up to 100");
+            require!(
+                percents_sum == PERCENTAGE_TOTAL,
+                "Percents do not sum up to 100%"
+            );
         }
         Ok(())
     }
 
+    fn validate_linear_schedule(&self, linear_schedule: &LinearUnlockSchedule) -> SCResult<()> {
+        require!(
+            linear_schedule.start_epoch < linear_schedule.end_epoch,
+            "Linear schedule end before start"
+        );
+        require!(
+            linear_schedule.start_epoch <= linear_schedule.cliff_epoch
+                && linear_schedule.cliff_epoch <= linear_schedule.end_epoch,
+            "Cliff outside of linear schedule"
+        );
+        Ok(())
+    }
+
     #[storage_mapper("locked_token_id")]
     fn locked_asset_token_id(&self) -> SingleValueMapper<Self::Storage, TokenIdentifier>;
 
     #[storage_mapper("locked_token_nonce")]
     fn locked_asset_token_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
+
+    #[storage_mapper("locked_asset_attributes")]
+    fn locked_asset_attributes(
+        &self,
+        nonce: Nonce,
+    ) -> SingleValueMapper<Self::Storage, LockedTokenAttributes>;
+
+    /// Units of a nonce in circulation, tracked for the nonces whose attributes are stored.
+    #[storage_mapper("locked_asset_supply")]
+    fn locked_asset_supply(&self, nonce: Nonce) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+
+    /// Units of a nonce the factory keeps for itself, as opposed to held deposits.
+    #[view(getRetainedSftAmount)]
+    #[storage_mapper("retained_sft_amount")]
+    fn retained_sft_amount(&self, nonce: Nonce) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+
+    #[view(getFirstTrackedSftNonce)]
+    #[storage_mapper("first_tracked_sft_nonce")]
+    fn first_tracked_sft_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
 }
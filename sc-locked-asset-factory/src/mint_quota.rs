@@ -0,0 +1,114 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use elrond_wasm::{require, sc_error};
+
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum MintQuotaPeriod {
+    Total,
+    PerEpoch,
+}
+
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct MintQuota<BigUint: BigUintApi> {
+    pub period: MintQuotaPeriod,
+    pub limit: BigUint,
+}
+
+/// Whitelisted callers without a quota are not limited.
+#[elrond_wasm_derive::module]
+pub trait MintQuotaModule {
+    #[endpoint(setMintQuota)]
+    fn set_mint_quota(
+        &self,
+        address: Address,
+        period: MintQuotaPeriod,
+        limit: Self::BigUint,
+    ) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.mint_quota(&address).set(&MintQuota { period, limit });
+        Ok(())
+    }
+
+    #[endpoint(removeMintQuota)]
+    fn remove_mint_quota(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(!self.mint_quota(&address).is_empty(), "No mint quota");
+        self.mint_quota(&address).clear();
+        Ok(())
+    }
+
+    #[endpoint(resetMintQuotaUsage)]
+    fn reset_mint_quota_usage(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        self.minted_amount(&address).clear();
+        self.minted_amount_epoch(&address).clear();
+        Ok(())
+    }
+
+    #[view(getMintQuota)]
+    fn get_mint_quota(&self, address: Address) -> OptionalResult<MintQuota<Self::BigUint>> {
+        if self.mint_quota(&address).is_empty() {
+            return OptionalResult::None;
+        }
+        OptionalResult::Some(self.mint_quota(&address).get())
+    }
+
+    #[view(getMintQuotaUsed)]
+    fn get_mint_quota_used(&self, address: Address) -> Self::BigUint {
+        self.get_minted_amount_in_period(&address)
+    }
+
+    #[view(getMintQuotaRemaining)]
+    fn get_mint_quota_remaining(&self, address: Address) -> OptionalResult<Self::BigUint> {
+        if self.mint_quota(&address).is_empty() {
+            return OptionalResult::None;
+        }
+
+        let limit = self.mint_quota(&address).get().limit;
+        let used = self.get_minted_amount_in_period(&address);
+        if used >= limit {
+            OptionalResult::Some(Self::BigUint::zero())
+        } else {
+            OptionalResult::Some(limit - used)
+        }
+    }
+
+    fn consume_mint_quota(&self, address: &Address, amount: &Self::BigUint) -> SCResult<()> {
+        let new_minted_amount = self.get_minted_amount_in_period(address) + amount.clone();
+        if !self.mint_quota(address).is_empty() {
+            require!(
+                new_minted_amount <= self.mint_quota(address).get().limit,
+                "Mint quota exceeded"
+            );
+        }
+
+        self.minted_amount(address).set(&new_minted_amount);
+        self.minted_amount_epoch(address)
+            .set(&self.blockchain().get_block_epoch());
+        Ok(())
+    }
+
+    fn get_minted_amount_in_period(&self, address: &Address) -> Self::BigUint {
+        let is_per_epoch = !self.mint_quota(address).is_empty()
+            && self.mint_quota(address).get().period == MintQuotaPeriod::PerEpoch;
+        if is_per_epoch
+            && self.minted_amount_epoch(address).get() != self.blockchain().get_block_epoch()
+        {
+            return Self::BigUint::zero();
+        }
+        self.minted_amount(address).get()
+    }
+
+    #[storage_mapper("mint_quota")]
+    fn mint_quota(
+        &self,
+        address: &Address,
+    ) -> SingleValueMapper<Self::Storage, MintQuota<Self::BigUint>>;
+
+    #[storage_mapper("minted_amount")]
+    fn minted_amount(&self, address: &Address) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+
+    #[storage_mapper("minted_amount_epoch")]
+    fn minted_amount_epoch(&self, address: &Address) -> SingleValueMapper<Self::Storage, u64>;
+}
@@ -0,0 +1,130 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+type Epoch = u64;
+type GrantId = u64;
+
+use super::asset_tokens;
+use super::early_unlock;
+use super::locked_asset;
+use distrib_common::*;
+use elrond_wasm::{require, sc_error};
+use modules::*;
+
+#[elrond_wasm_derive::module]
+pub trait RevocationModule:
+    asset::AssetModule
+    + locked_asset::LockedAssetModule
+    + asset_tokens::AssetTokensModule
+    + early_unlock::EarlyUnlockModule
+{
+    /// Stops vesting for every nonce of the grant and claws back the part of the granted
+    /// amount not vested in the current epoch. Holders can still unlock what vested.
+    #[endpoint]
+    fn revoke(&self, grant_id: GrantId) -> SCResult<()> {
+        require!(!self.grant_admin(grant_id).is_empty(), "Unknown grant");
+        require!(
+            self.blockchain().get_caller() == self.grant_admin(grant_id).get(),
+            "Permission denied"
+        );
+        require!(
+            self.grant_revoked_epoch(grant_id).is_empty(),
+            "Grant already revoked"
+        );
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.grant_revoked_epoch(grant_id).set(&current_epoch);
+
+        let grant_amount = self.grant_amount(grant_id).get();
+        let grant_schedule = LockedTokenAttributes {
+            unlock_milestones: self.grant_unlock_milestones(grant_id).get(),
+            linear_schedule: None,
+            unlocked_percent: 0,
+            revocable_grant: None,
+            asset_token_id: None,
+        };
+        let vested_amount = self.get_unlock_amount(&grant_amount, current_epoch, &grant_schedule);
+        let unvested_amount = grant_amount - vested_amount;
+        if unvested_amount > 0 {
+            self.release_penalty_tokens(&self.asset_token_id().get(), &unvested_amount);
+        }
+        Ok(())
+    }
+
+    fn create_revocable_grant(
+        &self,
+        beneficiary: Address,
+        admin: Address,
+        amount: &Self::BigUint,
+        unlock_milestones: &[UnlockMilestone],
+    ) -> RevocableGrant {
+        let grant_id = self.last_grant_id().get() + 1;
+        self.last_grant_id().set(&grant_id);
+        self.grant_admin(grant_id).set(&admin);
+        self.grant_amount(grant_id).set(amount);
+        self.grant_unlock_milestones(grant_id)
+            .set(&unlock_milestones.to_vec());
+
+        RevocableGrant {
+            grant_id,
+            beneficiary,
+            admin,
+        }
+    }
+
+    fn is_revoked(&self, attributes: &LockedTokenAttributes) -> bool {
+        match &attributes.revocable_grant {
+            Some(grant) => !self.grant_revoked_epoch(grant.grant_id).is_empty(),
+            None => false,
+        }
+    }
+
+    /// Revocable locked assets can only be unlocked by, and to, their beneficiary.
+    fn require_grant_beneficiary(
+        &self,
+        attributes: &LockedTokenAttributes,
+        caller: &Address,
+        receiver: &Address,
+    ) -> SCResult<()> {
+        if let Some(grant) = &attributes.revocable_grant {
+            require!(
+                caller == &grant.beneficiary && receiver == &grant.beneficiary,
+                "Only the beneficiary can unlock"
+            );
+        }
+        Ok(())
+    }
+
+    /// The epoch to compute unlocked amounts at: vesting stops at the revocation epoch.
+    fn get_vesting_epoch(&self, current_epoch: Epoch, attributes: &LockedTokenAttributes) -> Epoch {
+        let revoked_epoch_mapper = match &attributes.revocable_grant {
+            Some(grant) => self.grant_revoked_epoch(grant.grant_id),
+            None => return current_epoch,
+        };
+        if revoked_epoch_mapper.is_empty() {
+            return current_epoch;
+        }
+        core::cmp::min(current_epoch, revoked_epoch_mapper.get())
+    }
+
+    #[storage_mapper("last_grant_id")]
+    fn last_grant_id(&self) -> SingleValueMapper<Self::Storage, GrantId>;
+
+    #[view(getGrantAdmin)]
+    #[storage_mapper("grant_admin")]
+    fn grant_admin(&self, grant_id: GrantId) -> SingleValueMapper<Self::Storage, Address>;
+
+    #[view(getGrantAmount)]
+    #[storage_mapper("grant_amount")]
+    fn grant_amount(&self, grant_id: GrantId) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+
+    #[storage_mapper("grant_unlock_milestones")]
+    fn grant_unlock_milestones(
+        &self,
+        grant_id: GrantId,
+    ) -> SingleValueMapper<Self::Storage, Vec<UnlockMilestone>>;
+
+    #[view(getGrantRevokedEpoch)]
+    #[storage_mapper("grant_revoked_epoch")]
+    fn grant_revoked_epoch(&self, grant_id: GrantId) -> SingleValueMapper<Self::Storage, Epoch>;
+}
@@ -0,0 +1,148 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use super::locked_asset;
+use distrib_common::*;
+use elrond_wasm::{require, sc_error};
+use modules::*;
+
+type TemplateId = u64;
+
+/// Unlock milestones relative to the epoch the assets get locked in.
+/// Only `self_lockable` templates can be picked by holders through `lockAssets`.
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct UnlockScheduleTemplate {
+    pub name: BoxedBytes,
+    pub unlock_period: Vec<UnlockMilestone>,
+    pub active: bool,
+    pub self_lockable: bool,
+}
+
+#[elrond_wasm_derive::module]
+pub trait ScheduleCatalogueModule: asset::AssetModule + locked_asset::LockedAssetModule {
+    #[endpoint(addUnlockScheduleTemplate)]
+    fn add_unlock_schedule_template(
+        &self,
+        name: BoxedBytes,
+        self_lockable: bool,
+        #[var_args] unlock_period: VarArgs<UnlockMilestone>,
+    ) -> SCResult<TemplateId> {
+        only_owner!(self, "Permission denied");
+        require!(!name.is_empty(), "Empty name");
+        require!(!unlock_period.is_empty(), "Empty param");
+        self.validate_unlock_milestones(&unlock_period)?;
+
+        let template_id = self.last_template_id().get() + 1;
+        self.last_template_id().set(&template_id);
+        self.unlock_schedule_templates().insert(
+            template_id,
+            UnlockScheduleTemplate {
+                name,
+                unlock_period: unlock_period.into_vec(),
+                active: true,
+                self_lockable,
+            },
+        );
+        Ok(template_id)
+    }
+
+    #[endpoint(retireUnlockScheduleTemplate)]
+    fn retire_unlock_schedule_template(&self, template_id: TemplateId) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        let mut template = self.get_unlock_schedule_template(template_id)?;
+        require!(template.active, "Template already retired");
+
+        template.active = false;
+        self.unlock_schedule_templates()
+            .insert(template_id, template);
+        Ok(())
+    }
+
+    #[endpoint(setUnlockScheduleTemplateSelfLockable)]
+    fn set_unlock_schedule_template_self_lockable(
+        &self,
+        template_id: TemplateId,
+        self_lockable: bool,
+    ) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        let mut template = self.get_unlock_schedule_template(template_id)?;
+
+        template.self_lockable = self_lockable;
+        self.unlock_schedule_templates()
+            .insert(template_id, template);
+        Ok(())
+    }
+
+    /// Each template comes with the amount of the default asset token locked with it.
+    /// Amounts of the other asset tokens can be read with `getTemplateLockedAmount`.
+    #[view(getUnlockScheduleTemplates)]
+    fn get_unlock_schedule_templates(
+        &self,
+    ) -> MultiResultVec<MultiResult3<TemplateId, UnlockScheduleTemplate, Self::BigUint>> {
+        let asset_token_id = self.asset_token_id().get();
+        self.unlock_schedule_templates()
+            .iter()
+            .map(|(template_id, template)| {
+                let locked_amount = self
+                    .template_locked_amount(template_id, &asset_token_id)
+                    .get();
+                (template_id, template, locked_amount).into()
+            })
+            .collect::<Vec<MultiResult3<TemplateId, UnlockScheduleTemplate, Self::BigUint>>>()
+            .into()
+    }
+
+    fn get_unlock_schedule_template(
+        &self,
+        template_id: TemplateId,
+    ) -> SCResult<UnlockScheduleTemplate> {
+        match self.unlock_schedule_templates().get(&template_id) {
+            Some(template) => Ok(template),
+            None => sc_error!("Unknown template"),
+        }
+    }
+
+    fn get_active_unlock_period(&self, template_id: TemplateId) -> SCResult<Vec<UnlockMilestone>> {
+        let template = self.get_unlock_schedule_template(template_id)?;
+        require!(template.active, "Template retired");
+        Ok(template.unlock_period)
+    }
+
+    fn get_self_lockable_unlock_period(
+        &self,
+        template_id: TemplateId,
+    ) -> SCResult<Vec<UnlockMilestone>> {
+        let template = self.get_unlock_schedule_template(template_id)?;
+        require!(template.active, "Template retired");
+        require!(template.self_lockable, "Template not open for self lock");
+        Ok(template.unlock_period)
+    }
+
+    fn increase_template_locked_amount(
+        &self,
+        template_id: TemplateId,
+        token_id: &TokenIdentifier,
+        amount: &Self::BigUint,
+    ) {
+        let new_locked_amount =
+            self.template_locked_amount(template_id, token_id).get() + amount.clone();
+        self.template_locked_amount(template_id, token_id)
+            .set(&new_locked_amount);
+    }
+
+    #[storage_mapper("unlock_schedule_templates")]
+    fn unlock_schedule_templates(
+        &self,
+    ) -> MapMapper<Self::Storage, TemplateId, UnlockScheduleTemplate>;
+
+    #[storage_mapper("last_template_id")]
+    fn last_template_id(&self) -> SingleValueMapper<Self::Storage, TemplateId>;
+
+    #[view(getTemplateLockedAmount)]
+    #[storage_mapper("template_locked_amount")]
+    fn template_locked_amount(
+        &self,
+        template_id: TemplateId,
+        token_id: &TokenIdentifier,
+    ) -> SingleValueMapper<Self::Storage, Self::BigUint>;
+}
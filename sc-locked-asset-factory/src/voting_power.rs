@@ -0,0 +1,133 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+type Nonce = u64;
+type Epoch = u64;
+
+use super::asset_tokens;
+use super::locked_asset;
+use super::revocation;
+use distrib_common::*;
+use elrond_wasm::{require, sc_error};
+use modules::*;
+
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum VotingPowerCurveType {
+    Linear,
+    Quadratic,
+}
+
+/// Weight of a locked portion as a function of the epochs left until it unlocks.
+/// Portions locked for `max_lock_epochs` or more count in full.
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct VotingPowerCurve {
+    pub curve_type: VotingPowerCurveType,
+    pub max_lock_epochs: u64,
+}
+
+#[elrond_wasm_derive::module]
+pub trait VotingPowerModule:
+    asset::AssetModule
+    + locked_asset::LockedAssetModule
+    + revocation::RevocationModule
+    + asset_tokens::AssetTokensModule
+{
+    #[endpoint(setVotingPowerCurve)]
+    fn set_voting_power_curve(
+        &self,
+        curve_type: VotingPowerCurveType,
+        max_lock_epochs: u64,
+    ) -> SCResult<()> {
+        only_owner!(self, "Permission denied");
+        require!(max_lock_epochs > 0, "Zero max lock epochs");
+
+        self.voting_power_curve().set(&VotingPowerCurve {
+            curve_type,
+            max_lock_epochs,
+        });
+        Ok(())
+    }
+
+    #[view(getVotingPower)]
+    fn get_voting_power(
+        &self,
+        token_nonce: Nonce,
+        amount: Self::BigUint,
+        epoch: Epoch,
+    ) -> SCResult<Self::BigUint> {
+        require!(
+            !self.voting_power_curve().is_empty(),
+            "Voting power curve not set"
+        );
+        self.compute_voting_power(token_nonce, &amount, epoch)
+    }
+
+    #[view(getAggregateVotingPower)]
+    fn get_aggregate_voting_power(
+        &self,
+        epoch: Epoch,
+        #[var_args] locked_assets: VarArgs<MultiArg2<Nonce, Self::BigUint>>,
+    ) -> SCResult<Self::BigUint> {
+        require!(
+            !self.voting_power_curve().is_empty(),
+            "Voting power curve not set"
+        );
+
+        let mut voting_power = Self::BigUint::zero();
+        for locked_asset in locked_assets.into_vec() {
+            let (token_nonce, amount) = locked_asset.into_tuple();
+            voting_power += self.compute_voting_power(token_nonce, &amount, epoch)?;
+        }
+        Ok(voting_power)
+    }
+
+    /// Only the default asset token gives voting power.
+    /// Revoked grants have none, their unvested part is clawed back.
+    fn compute_voting_power(
+        &self,
+        token_nonce: Nonce,
+        amount: &Self::BigUint,
+        epoch: Epoch,
+    ) -> SCResult<Self::BigUint> {
+        let attributes = self.get_stored_attributes(token_nonce)?;
+        if self.is_revoked(&attributes)
+            || self.get_asset_token_id(&attributes) != self.asset_token_id().get()
+        {
+            return Ok(Self::BigUint::zero());
+        }
+
+        let curve = self.voting_power_curve().get();
+        let max_lock_epochs = Self::BigUint::from(curve.max_lock_epochs);
+        let unlock_amounts = self.get_remaining_unlock_amounts(amount, &attributes);
+        let mut voting_power = Self::BigUint::zero();
+        for (unlock_epoch, unlock_amount) in unlock_amounts.into_iter() {
+            // Milestones unlock in the epoch after their unlock epoch.
+            let epochs_left = core::cmp::min(
+                (unlock_epoch + 1).saturating_sub(epoch),
+                curve.max_lock_epochs,
+            );
+            let epochs_left = Self::BigUint::from(epochs_left);
+            voting_power += match curve.curve_type {
+                VotingPowerCurveType::Linear => {
+                    unlock_amount * epochs_left / max_lock_epochs.clone()
+                }
+                VotingPowerCurveType::Quadratic => {
+                    unlock_amount * epochs_left.clone() * epochs_left
+                        / (max_lock_epochs.clone() * max_lock_epochs.clone())
+                }
+            };
+        }
+        Ok(voting_power)
+    }
+
+    #[view(getVotingPowerCurve)]
+    fn get_voting_power_curve(&self) -> OptionalResult<VotingPowerCurve> {
+        if self.voting_power_curve().is_empty() {
+            return OptionalResult::None;
+        }
+        OptionalResult::Some(self.voting_power_curve().get())
+    }
+
+    #[storage_mapper("voting_power_curve")]
+    fn voting_power_curve(&self) -> SingleValueMapper<Self::Storage, VotingPowerCurve>;
+}
@@ -9,6 +9,7 @@ mod proxy_pair;
 
 use crate::proxy_farm::*;
 use crate::proxy_pair::*;
+use modules::*;
 
 #[derive(TopEncode, TopDecode, TypeAbi)]
 pub enum IssueRequestType {
@@ -18,7 +19,11 @@ pub enum IssueRequestType {
 
 #[elrond_wasm_derive::contract]
 pub trait ProxyDexImpl:
-    proxy_common::ProxyCommonModule + proxy_pair::ProxyPairModule + proxy_farm::ProxyFarmModule
+    blocklist::BlocklistModule
+    + proxy_common::ProxyCommonModule
+    + proxy_pair::ProxyPairModule
+    + proxy_farm::ProxyFarmModule
+    + token_metadata::TokenMetadataModule
 {
     #[init]
     fn init(
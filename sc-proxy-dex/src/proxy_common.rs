@@ -1,8 +1,15 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
+use distrib_common::*;
+use modules::*;
+
+type Nonce = u64;
+
 #[elrond_wasm_derive::module]
-pub trait ProxyCommonModule {
+pub trait ProxyCommonModule:
+    blocklist::BlocklistModule + token_metadata::TokenMetadataModule
+{
     #[endpoint(addAcceptedLockedAssetTokenId)]
     fn add_accepted_locked_asset_token_id(&self, token_id: TokenIdentifier) -> SCResult<()> {
         self.require_permissions()?;
@@ -26,6 +33,37 @@ pub trait ProxyCommonModule {
         Ok(())
     }
 
+    /// Revocable locked assets would escape a revocation while held by the proxy.
+    /// Locked assets of other tokens than the asset token cannot be minted by the proxy.
+    fn require_supported_locked_asset(
+        &self,
+        token_id: &TokenIdentifier,
+        token_nonce: Nonce,
+    ) -> SCResult<()> {
+        let token_info = self.blockchain().get_esdt_token_data(
+            &self.blockchain().get_sc_address(),
+            token_id.as_esdt_identifier(),
+            token_nonce,
+        );
+
+        match token_info.decode_attributes::<LockedTokenAttributes>() {
+            Result::Ok(attributes) => {
+                require!(
+                    attributes.revocable_grant.is_none(),
+                    "Revocable locked assets not accepted"
+                );
+                if let Some(asset_token_id) = attributes.asset_token_id {
+                    require!(
+                        asset_token_id == self.asset_token_id().get(),
+                        "Locked asset token not accepted"
+                    );
+                }
+                Ok(())
+            }
+            Result::Err(_) => sc_error!("Decoding error"),
+        }
+    }
+
     fn require_permissions(&self) -> SCResult<()> {
         only_owner!(self, "Permission denied");
         Ok(())
@@ -80,6 +80,8 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         self.require_wrapped_lp_token_id_not_empty()?;
         let proxy_params = self.proxy_farm_params().get();
 
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         let token_nonce = self.call_value().esdt_token_nonce();
         let (amount, token_id) = self.call_value().payment_token_pair();
         require!(amount != 0, "Payment amount cannot be zero");
@@ -90,6 +92,7 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
                 self.get_wrapped_lp_token_attributes(&token_id, token_nonce)?;
             to_farm_token_id = wrapped_lp_token_attrs.lp_token_id;
         } else if self.accepted_locked_assets().contains(&token_id) {
+            self.require_supported_locked_asset(&token_id, token_nonce)?;
             let asset_token_id = self.asset_token_id().get();
             self.send().esdt_local_mint(
                 min(
@@ -125,7 +128,6 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
             farmed_token_id: token_id,
             farmed_token_nonce: token_nonce,
         };
-        let caller = self.blockchain().get_caller();
         self.create_and_send_wrapped_farm_tokens(&attributes, &farm_token_total_amount, &caller);
 
         Ok(())
@@ -140,6 +142,8 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         self.require_wrapped_lp_token_id_not_empty()?;
         let proxy_params = self.proxy_farm_params().get();
 
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         let token_nonce = self.call_value().esdt_token_nonce();
         let (amount, token_id) = self.call_value().payment_token_pair();
         require!(amount != 0, "Payment amount cannot be zero");
@@ -163,7 +167,6 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         let farmed_token_returned = farm_result.0;
         let reward_token_returned = farm_result.1;
 
-        let caller = self.blockchain().get_caller();
         self.send().transfer_tokens(
             &wrapped_farm_token_attrs.farmed_token_id,
             wrapped_farm_token_attrs.farmed_token_nonce,
@@ -210,6 +213,8 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         self.require_wrapped_lp_token_id_not_empty()?;
         let proxy_params = self.proxy_farm_params().get();
 
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         let token_nonce = self.call_value().esdt_token_nonce();
         let (amount, token_id) = self.call_value().payment_token_pair();
         require!(amount != 0, "Payment amount cannot be zero");
@@ -248,7 +253,6 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         );
 
         // Send the reward to the caller.
-        let caller = self.blockchain().get_caller();
         self.send().transfer_tokens(
             &reward_token_returned.token_id,
             reward_token_returned.token_nonce,
@@ -299,8 +303,7 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         address: &Address,
     ) {
         let wrapped_farm_token_id = self.wrapped_farm_token_id().get();
-        self.create_wrapped_farm_tokens(&wrapped_farm_token_id, attributes, amount);
-        let nonce = self.wrapped_farm_token_nonce().get();
+        let nonce = self.create_wrapped_farm_tokens(&wrapped_farm_token_id, attributes, amount);
         self.send()
             .transfer_tokens(&wrapped_farm_token_id, nonce, amount, address);
     }
@@ -310,18 +313,21 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
         token_id: &TokenIdentifier,
         attributes: &WrappedFarmTokenAttributes,
         amount: &Self::BigUint,
-    ) {
-        self.send().esdt_nft_create::<WrappedFarmTokenAttributes>(
+    ) -> Nonce {
+        let encoded_attributes =
+            elrond_wasm::elrond_codec::top_encode_to_vec(attributes).unwrap_or_default();
+        let attributes_hash = self.hash_token_attributes(encoded_attributes.as_slice());
+        let new_nonce = self.send().esdt_nft_create::<WrappedFarmTokenAttributes>(
             self.blockchain().get_gas_left(),
             token_id.as_esdt_identifier(),
             amount,
-            &BoxedBytes::empty(),
+            &attributes.token_name(),
             &Self::BigUint::zero(),
-            &H256::zero(),
+            &attributes_hash,
             &attributes,
-            &[BoxedBytes::empty()],
+            self.get_token_uris(&attributes_hash).as_slice(),
         );
-        self.increase_wrapped_farm_token_nonce();
+        new_nonce
     }
 
     fn actual_enter_farm(
@@ -383,12 +389,6 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
             .execute_on_dest_context_custom_range(gas_limit, |_, after| (after-2, after))
     }
 
-    fn increase_wrapped_farm_token_nonce(&self) -> Nonce {
-        let new_nonce = self.wrapped_farm_token_nonce().get() + 1;
-        self.wrapped_farm_token_nonce().set(&new_nonce);
-        new_nonce
-    }
-
     fn require_is_intermediated_farm(&self, address: &Address) -> SCResult<()> {
         require!(
             self.intermediated_farms().contains(address),
@@ -415,9 +415,6 @@ pub trait ProxyFarmModule: proxy_common::ProxyCommonModule + proxy_pair::ProxyPa
     #[storage_mapper("wrapped_farm_token_id")]
     fn wrapped_farm_token_id(&self) -> SingleValueMapper<Self::Storage, TokenIdentifier>;
 
-    #[storage_mapper("wrapped_farm_token_nonce")]
-    fn wrapped_farm_token_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
-
     #[storage_mapper("proxy_farm_params")]
     fn proxy_farm_params(&self) -> SingleValueMapper<Self::Storage, ProxyFarmParams>;
 }
@@ -66,11 +66,16 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
     fn accept_esdt_payment_proxy(&self, pair_address: Address) -> SCResult<()> {
         self.require_is_intermediated_pair(&pair_address)?;
 
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
+
         let token_nonce = self.call_value().esdt_token_nonce();
         let (amount, token_id) = self.call_value().payment_token_pair();
         require!(amount != 0, "Payment amount cannot be zero");
+        if self.accepted_locked_assets().contains(&token_id) {
+            self.require_supported_locked_asset(&token_id, token_nonce)?;
+        }
 
-        let caller = self.blockchain().get_caller();
         self.increase_temporary_funds_amount(&caller, &token_id, token_nonce, &amount);
         Ok(())
     }
@@ -84,6 +89,7 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
         second_token_nonce: Nonce,
     ) -> SCResult<()> {
         let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         self.send_temporary_funds_back(&caller, &first_token_id, first_token_nonce);
         self.send_temporary_funds_back(&caller, &second_token_id, second_token_nonce);
         Ok(())
@@ -108,6 +114,7 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
         let proxy_params = self.proxy_pair_params().get();
 
         let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         require!(first_token_id != second_token_id, "Identical tokens");
         require!(
             (first_token_nonce == 0 && second_token_nonce != 0)
@@ -262,6 +269,8 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
         self.require_wrapped_lp_token_id_not_empty()?;
         let proxy_params = self.proxy_pair_params().get();
 
+        let caller = self.blockchain().get_caller();
+        self.require_not_blocklisted(&caller)?;
         let token_nonce = self.call_value().esdt_token_nonce();
         require!(token_nonce != 0, "Can only be called with an SFT");
         let (amount, token_id) = self.call_value().payment_token_pair();
@@ -270,7 +279,6 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
         let wrapped_lp_token_id = self.wrapped_lp_token_id().get();
         require!(token_id == wrapped_lp_token_id, "Wrong input token");
 
-        let caller = self.blockchain().get_caller();
         let lp_token_id = self.ask_for_lp_token_id(&pair_address, &proxy_params);
         let attributes = self.get_wrapped_lp_token_attributes(&token_id, token_nonce)?;
         require!(lp_token_id == attributes.lp_token_id, "Bad input address");
@@ -488,18 +496,22 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
             self.blockchain().get_gas_left(),
             proxy_params.mint_tokens_gas_limit,
         );
-        self.send()
+        let encoded_attributes =
+            elrond_wasm::elrond_codec::top_encode_to_vec(&attributes).unwrap_or_default();
+        let attributes_hash = self.hash_token_attributes(encoded_attributes.as_slice());
+        let new_nonce = self
+            .send()
             .esdt_nft_create::<WrappedLpTokenAttributes<Self::BigUint>>(
                 gas_limit,
                 wrapped_lp_token_id.as_esdt_identifier(),
                 lp_token_amount,
-                &BoxedBytes::empty(),
+                &attributes.token_name(),
                 &Self::BigUint::zero(),
-                &H256::zero(),
+                &attributes_hash,
                 &attributes,
-                &[BoxedBytes::empty()],
+                self.get_token_uris(&attributes_hash).as_slice(),
             );
-        self.increase_wrapped_lp_token_nonce()
+        new_nonce
     }
 
     fn send_temporary_funds_back(
@@ -559,12 +571,6 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
             .set(&new_amount);
     }
 
-    fn increase_wrapped_lp_token_nonce(&self) -> Nonce {
-        let new_nonce = self.wrapped_lp_token_nonce().get() + 1;
-        self.wrapped_lp_token_nonce().set(&new_nonce);
-        new_nonce
-    }
-
     fn decrease_temporary_funds_amount(
         &self,
         caller: &Address,
@@ -617,9 +623,6 @@ pub trait ProxyPairModule: proxy_common::ProxyCommonModule {
     #[storage_mapper("wrapped_lp_token_id")]
     fn wrapped_lp_token_id(&self) -> SingleValueMapper<Self::Storage, TokenIdentifier>;
 
-    #[storage_mapper("wrapped_tp_token_nonce")]
-    fn wrapped_lp_token_nonce(&self) -> SingleValueMapper<Self::Storage, Nonce>;
-
     #[storage_mapper("proxy_pair_params")]
     fn proxy_pair_params(&self) -> SingleValueMapper<Self::Storage, ProxyPairParams>;
 }